@@ -1,8 +1,10 @@
-use crate::error::AppResult;
-use crate::paths::get_dilag_dir;
+use crate::error::{AppError, AppResult};
+use crate::opencode::{BunCheckResult, OpenCodeCheckResult};
+use crate::paths::{get_data_dir_bootstrap_file, get_dilag_dir, get_sessions_file, DATA_DIR_ENV};
+use crate::state::AppState;
 use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
 #[derive(Debug, Serialize)]
@@ -12,7 +14,40 @@ pub struct AppInfo {
     pub data_size_bytes: u64,
 }
 
-fn calculate_dir_size(path: &PathBuf) -> u64 {
+/// Aggregated startup status so the frontend can hydrate in a single IPC call.
+#[derive(Debug, Serialize)]
+pub struct AppStatus {
+    pub opencode_running: bool,
+    pub opencode_port: Option<u16>,
+    pub opencode_uptime_secs: Option<u64>,
+    pub opencode: OpenCodeCheckResult,
+    pub bun: BunCheckResult,
+}
+
+/// Environment report for support requests, shown in the UI for copying
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub os: String,
+    pub arch: String,
+    pub app_version: String,
+    pub opencode: OpenCodeCheckResult,
+    pub bun: BunCheckResult,
+    pub data_dir: String,
+    pub data_dir_writable: bool,
+    /// Data is in a temp dir because no home directory could be resolved
+    pub data_dir_fallback: bool,
+    pub free_disk_bytes: Option<u64>,
+    pub opencode_running: bool,
+    pub opencode_port: Option<u16>,
+    /// Whether the port is bindable; expected to be false while the server runs
+    pub opencode_port_free: Option<bool>,
+    /// PATH used for spawned commands (see `get_effective_path`)
+    pub effective_path: String,
+}
+
+/// Total size of the files under `path`. Symlinks aren't followed, so
+/// linked package stores in `node_modules` can't loop or double count.
+pub(crate) fn calculate_dir_size(path: &Path) -> u64 {
     if !path.exists() {
         return 0;
     }
@@ -22,9 +57,8 @@ fn calculate_dir_size(path: &PathBuf) -> u64 {
             entries
                 .filter_map(|e| e.ok())
                 .map(|entry| {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        calculate_dir_size(&path)
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                        calculate_dir_size(&entry.path())
                     } else {
                         entry.metadata().map(|m| m.len()).unwrap_or(0)
                     }
@@ -34,6 +68,68 @@ fn calculate_dir_size(path: &PathBuf) -> u64 {
         .unwrap_or(0)
 }
 
+/// Installs bail out below this much free space instead of failing mid-write
+pub(crate) const MIN_INSTALL_FREE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Free bytes available to the current user on the volume containing `path`.
+/// Walks up to the nearest existing ancestor so not-yet-created dirs work.
+pub(crate) fn free_space_bytes(path: &Path) -> AppResult<u64> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| format!("No existing parent for {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = CString::new(existing.as_os_str().as_bytes())
+            .map_err(|e| format!("Invalid path: {}", e))?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        #[allow(clippy::unnecessary_cast)]
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+
+    #[cfg(windows)]
+    {
+        let script = format!(
+            "([System.IO.DriveInfo]::new('{}')).AvailableFreeSpace",
+            existing.display().to_string().replace('\'', "''")
+        );
+        let output = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .output()?;
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .map_err(|_| "Failed to read free disk space".into())
+    }
+}
+
+/// Error out early with an actionable message when `path`'s volume is nearly full
+pub(crate) fn ensure_free_space(path: &Path) -> AppResult<()> {
+    let free = free_space_bytes(path)?;
+    if free < MIN_INSTALL_FREE_BYTES {
+        return Err(format!(
+            "Insufficient disk space: {} MB free, at least {} MB required",
+            free / (1024 * 1024),
+            MIN_INSTALL_FREE_BYTES / (1024 * 1024)
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Free bytes on the volume containing `path`, for display in the UI
+#[tauri::command]
+pub fn check_free_space(path: String) -> AppResult<u64> {
+    free_space_bytes(Path::new(&path))
+}
+
 #[tauri::command]
 pub async fn get_app_info() -> AppInfo {
     let dilag_dir = get_dilag_dir();
@@ -51,15 +147,205 @@ pub async fn get_app_info() -> AppInfo {
     }
 }
 
+/// Combines server state and dependency checks that the frontend would
+/// otherwise fetch with separate `is_opencode_running` / `check_*` calls.
+#[tauri::command]
+pub async fn get_app_status(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<AppStatus> {
+    let opencode_running = state.opencode_pid.lock().unwrap().is_some();
+    let opencode_port = *state.opencode_port.lock().unwrap();
+    let opencode_uptime_secs = state
+        .opencode_started_at
+        .lock()
+        .unwrap()
+        .map(|started| started.elapsed().as_secs());
+
+    let opencode = crate::opencode::check_opencode_installation(app.clone()).await;
+    let bun = crate::opencode::check_bun_installation(app).await;
+
+    Ok(AppStatus {
+        opencode_running,
+        opencode_port,
+        opencode_uptime_secs,
+        opencode,
+        bun,
+    })
+}
+
+/// Move a file or directory, copying across volumes when a rename isn't possible
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_path(from, to)?;
+    if from.is_dir() && !from.is_symlink() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+/// Recursive copy that keeps symlinks (synced skills are symlinks) as links
+fn copy_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    let meta = fs::symlink_metadata(from)?;
+    if meta.file_type().is_symlink() {
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(fs::read_link(from)?, to);
+        #[cfg(windows)]
+        return fs::copy(from, to).map(|_| ());
+    }
+    if meta.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)?.flatten() {
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Point session `cwd`s at the new data directory after a move
+fn rewrite_session_cwds(old_root: &Path, new_root: &Path) -> AppResult<()> {
+    let file_path = get_sessions_file();
+    if !file_path.exists() {
+        return Ok(());
+    }
+    let mut store = crate::sessions::load_sessions_store();
+    for session in &mut store.sessions {
+        if let Ok(rest) = Path::new(&session.cwd).strip_prefix(old_root) {
+            session.cwd = new_root.join(rest).to_string_lossy().to_string();
+        }
+    }
+    crate::sessions::save_sessions_store(&store)
+}
+
+/// Use `path` as the data directory from now on, optionally moving existing
+/// data into it. Refused while the app is busy or the OpenCode server is
+/// running, since both may be writing into the directory. Restart Dilag
+/// afterwards so the OpenCode server picks up the new config location.
+#[tauri::command]
+pub fn set_data_dir(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    move_existing: bool,
+) -> AppResult<String> {
+    state.ensure_not_busy()?;
+    if state.opencode_pid.lock().unwrap().is_some() {
+        return Err(AppError::Busy(
+            "Stop the OpenCode server before changing the data directory".to_string(),
+        ));
+    }
+    if std::env::var_os(DATA_DIR_ENV).is_some_and(|v| !v.is_empty()) {
+        return Err(format!("The data directory is set by {} and can't be changed here", DATA_DIR_ENV).into());
+    }
+
+    let target = PathBuf::from(&path);
+    if !target.is_absolute() {
+        return Err(format!("Data directory must be an absolute path: {}", path).into());
+    }
+    fs::create_dir_all(&target)?;
+    let target = fs::canonicalize(&target)?;
+
+    let current = get_dilag_dir();
+    let current = fs::canonicalize(&current).unwrap_or(current);
+    if target == current {
+        return Ok(target.to_string_lossy().to_string());
+    }
+    if target.starts_with(&current) || current.starts_with(&target) {
+        return Err("The new data directory can't be inside the current one, or vice versa".into());
+    }
+
+    // Writability check
+    let probe = target.join(".dilag-write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("Data directory is not writable: {}", e))?;
+    fs::remove_file(&probe)?;
+
+    if move_existing && current.exists() {
+        if fs::read_dir(&target)?.next().is_some() {
+            return Err("The new data directory must be empty to move existing data into it".into());
+        }
+        for entry in fs::read_dir(&current)?.flatten() {
+            move_path(&entry.path(), &target.join(entry.file_name()))?;
+        }
+    }
+
+    let bootstrap = get_data_dir_bootstrap_file().ok_or("Could not find the config directory")?;
+    if let Some(parent) = bootstrap.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&bootstrap, target.to_string_lossy().as_bytes())?;
+
+    if move_existing {
+        rewrite_session_cwds(&current, &target)?;
+    }
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Collect environment checks into one report. Contains no credentials.
+#[tauri::command]
+pub async fn run_diagnostics(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Diagnostics> {
+    let opencode_running = state.opencode_pid.lock().unwrap().is_some();
+    let opencode_port = *state.opencode_port.lock().unwrap();
+
+    let data_dir = get_dilag_dir();
+    let data_dir_writable = fs::create_dir_all(&data_dir).is_ok() && {
+        let probe = data_dir.join(".dilag-write-test");
+        let ok = fs::write(&probe, b"ok").is_ok();
+        let _ = fs::remove_file(&probe);
+        ok
+    };
+
+    let opencode = crate::opencode::check_opencode_installation(app.clone()).await;
+    let bun = crate::opencode::check_bun_installation(app).await;
+
+    Ok(Diagnostics {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        opencode,
+        bun,
+        free_disk_bytes: free_space_bytes(&data_dir).ok(),
+        data_dir: data_dir.to_string_lossy().to_string(),
+        data_dir_writable,
+        data_dir_fallback: crate::paths::is_data_dir_fallback(),
+        opencode_running,
+        opencode_port,
+        opencode_port_free: opencode_port.map(crate::opencode::is_port_free),
+        effective_path: crate::opencode::build_augmented_path(),
+    })
+}
+
+/// Mark the app busy so destructive commands (reset, session delete) refuse
+/// until `clear_busy`. The frontend sets this while designs are generating.
+#[tauri::command]
+pub fn set_busy(state: tauri::State<'_, AppState>, reason: String) {
+    *state.busy_reason.lock().unwrap() = Some(reason);
+}
+
+#[tauri::command]
+pub fn clear_busy(state: tauri::State<'_, AppState>) {
+    state.busy_reason.lock().unwrap().take();
+}
+
 #[tauri::command]
 pub async fn reset_all_data(
     app: AppHandle,
-    state: tauri::State<'_, crate::state::AppState>,
+    state: tauri::State<'_, AppState>,
 ) -> AppResult<()> {
+    state.ensure_not_busy()?;
+
     // Stop the opencode server first
     {
         let mut pid_guard = state.opencode_pid.lock().unwrap();
         pid_guard.take();
+        state.opencode_started_at.lock().unwrap().take();
     }
 
     // Delete the entire .dilag directory contents
@@ -3,6 +3,59 @@
 //! Native WebKit capture was removed due to main thread dispatch complexity.
 //! Frontend uses html2canvas for all capture operations.
 
+use crate::designs::extract_html_attr;
+use crate::sessions::load_sessions_store;
+use image::{imageops, RgbaImage};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Default capture size for mobile screens, matching the canvas frame
+/// (`DEFAULT_MOBILE_SCREEN_WIDTH/HEIGHT` in `screen-node.tsx`).
+pub const MOBILE_CAPTURE_SIZE: (u32, u32) = (393, 852);
+
+/// Default capture size for web screens, matching the "desktop" viewport
+/// in `design-mode-store.ts`.
+pub const WEB_CAPTURE_SIZE: (u32, u32) = (1280, 800);
+
+/// Map a screen type (`"mobile"` / `"web"`) to its default capture size.
+/// Unknown types are treated as web.
+pub fn default_capture_size(screen_type: &str) -> (u32, u32) {
+    if screen_type.eq_ignore_ascii_case("mobile") {
+        MOBILE_CAPTURE_SIZE
+    } else {
+        WEB_CAPTURE_SIZE
+    }
+}
+
+/// Resolve capture size from the design's `data-screen-type`, falling back
+/// to the session platform, then to web.
+fn resolve_capture_size(html: &str, platform: Option<&str>) -> (u32, u32) {
+    let screen_type = extract_html_attr(html, "data-screen-type");
+    default_capture_size(screen_type.as_deref().or(platform).unwrap_or("web"))
+}
+
+/// Why native capture is unavailable; every build currently reports it
+const NATIVE_CAPTURE_UNAVAILABLE: &str =
+    "Native WebKit capture was removed; render with html2canvas instead";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSupport {
+    pub supported: bool,
+    /// Set when `supported` is false
+    pub reason: Option<String>,
+}
+
+/// Whether `capture_html_to_image` can render natively, so
+/// the frontend can go straight to html2canvas instead of trying first
+#[tauri::command]
+pub fn capture_supported() -> CaptureSupport {
+    CaptureSupport {
+        supported: false,
+        reason: Some(NATIVE_CAPTURE_UNAVAILABLE.to_string()),
+    }
+}
+
 /// Tauri command to capture HTML as PNG image
 ///
 /// Always returns an error - frontend should use html2canvas.
@@ -13,5 +66,175 @@ pub async fn capture_html_to_image(
     _height: u32,
     _scale: f32,
 ) -> Result<Vec<u8>, String> {
-    Err("Native capture not supported. Use html2canvas fallback.".into())
+    Err(NATIVE_CAPTURE_UNAVAILABLE.into())
+}
+
+/// What the frontend renderer needs to capture one design
+#[derive(Debug, Clone, Serialize)]
+pub struct DesignCapture {
+    pub html: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Resolve a design file and its capture size for html2canvas.
+///
+/// Looks `filename` up among the session's design files (`screens/` wins
+/// over flow folders and the session root). Sizes default to
+/// [`MOBILE_CAPTURE_SIZE`] or [`WEB_CAPTURE_SIZE`] from the screen type;
+/// `width`/`height` override them.
+#[tauri::command]
+pub fn resolve_design_capture(
+    session_cwd: String,
+    filename: String,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<DesignCapture, String> {
+    if filename.contains(['/', '\\']) || filename.contains("..") {
+        return Err(format!("Invalid design filename: {}", filename));
+    }
+
+    let path = crate::designs::resolve_design_files(Path::new(&session_cwd))
+        .into_iter()
+        .find(|p| p.file_name().is_some_and(|n| n == filename.as_str()))
+        .ok_or_else(|| format!("Design not found: {}", filename))?;
+    let html = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", filename, e))?;
+
+    let platform = load_sessions_store()
+        .sessions
+        .into_iter()
+        .find(|s| s.cwd == session_cwd)
+        .and_then(|s| s.platform);
+    let (default_width, default_height) = resolve_capture_size(&html, platform.as_deref());
+
+    Ok(DesignCapture {
+        html,
+        width: width.unwrap_or(default_width),
+        height: height.unwrap_or(default_height),
+    })
+}
+
+/// A bundled phone bezel (`assets/frames/`). The PNG is transparent where
+/// the screen shows through; `screen` is that safe-area rect `(x, y, w, h)`
+/// at 1x, sized to [`MOBILE_CAPTURE_SIZE`].
+struct DeviceFrame {
+    name: &'static str,
+    png: &'static [u8],
+    screen: (u32, u32, u32, u32),
+}
+
+const DEVICE_FRAMES: &[DeviceFrame] = &[
+    DeviceFrame {
+        name: "iphone-14-pro",
+        png: include_bytes!("../assets/frames/iphone-14-pro.png"),
+        screen: (18, 18, 393, 852),
+    },
+    DeviceFrame {
+        name: "pixel-7",
+        png: include_bytes!("../assets/frames/pixel-7.png"),
+        screen: (16, 16, 393, 852),
+    },
+];
+
+fn find_device_frame(name: &str) -> Result<&'static DeviceFrame, String> {
+    DEVICE_FRAMES.iter().find(|f| f.name == name).ok_or_else(|| {
+        let known: Vec<&str> = DEVICE_FRAMES.iter().map(|f| f.name).collect();
+        format!("Unknown device frame: {} (available: {})", name, known.join(", "))
+    })
+}
+
+/// Place a rendered screen into the frame's safe area and draw the bezel on
+/// top. The frame is scaled to the screenshot's resolution, so a 2x capture
+/// yields a 2x framed image.
+fn composite_into_frame(screen_png: &[u8], frame: &DeviceFrame) -> Result<Vec<u8>, String> {
+    let screen = image::load_from_memory(screen_png)
+        .map_err(|e| format!("Invalid screen image: {}", e))?
+        .to_rgba8();
+    let bezel = image::load_from_memory(frame.png)
+        .map_err(|e| format!("Invalid device frame {}: {}", frame.name, e))?
+        .to_rgba8();
+
+    let (x, y, w, h) = frame.screen;
+    let factor = screen.width() as f64 / w as f64;
+    let scaled = |v: u32| (v as f64 * factor).round() as u32;
+    let bezel = if factor == 1.0 {
+        bezel
+    } else {
+        imageops::resize(
+            &bezel,
+            scaled(bezel.width()),
+            scaled(bezel.height()),
+            imageops::FilterType::Lanczos3,
+        )
+    };
+    let screen = if screen.height() == scaled(h) {
+        screen
+    } else {
+        imageops::resize(&screen, scaled(w), scaled(h), imageops::FilterType::Lanczos3)
+    };
+
+    let mut framed = RgbaImage::new(bezel.width(), bezel.height());
+    imageops::overlay(&mut framed, &screen, scaled(x) as i64, scaled(y) as i64);
+    imageops::overlay(&mut framed, &bezel, 0, 0);
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    framed
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode framed image: {}", e))?;
+    Ok(png.into_inner())
+}
+
+/// Place a mobile screen rendered by html2canvas (393×852 or a multiple)
+/// inside a device bezel such as `"iphone-14-pro"`, for marketing shots and
+/// exports. Fails for unknown frame names.
+#[tauri::command]
+pub fn frame_screenshot(png: Vec<u8>, frame: String) -> Result<Vec<u8>, String> {
+    composite_into_frame(&png, find_device_frame(&frame)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_screen_type_to_default_size() {
+        assert_eq!(default_capture_size("mobile"), (393, 852));
+        assert_eq!(default_capture_size("web"), (1280, 800));
+        assert_eq!(default_capture_size("tablet"), WEB_CAPTURE_SIZE);
+    }
+
+    #[test]
+    fn screen_type_attribute_wins_over_session_platform() {
+        let html = r#"<html data-screen-type="mobile"><body></body></html>"#;
+        assert_eq!(resolve_capture_size(html, Some("web")), MOBILE_CAPTURE_SIZE);
+    }
+
+    #[test]
+    fn composites_screen_into_frame_safe_area() {
+        let frame = find_device_frame("iphone-14-pro").unwrap();
+        let screen = RgbaImage::from_pixel(393 * 2, 852 * 2, image::Rgba([255, 0, 0, 255]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        screen.write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+        let framed = composite_into_frame(&png.into_inner(), frame).unwrap();
+        let framed = image::load_from_memory(&framed).unwrap().to_rgba8();
+        let bezel = image::load_from_memory(frame.png).unwrap();
+        assert_eq!(framed.dimensions(), (bezel.width() * 2, bezel.height() * 2));
+        // Middle of the safe area shows the screen, the outer corner stays clear
+        assert_eq!(framed.get_pixel(framed.width() / 2, framed.height() / 2).0, [255, 0, 0, 255]);
+        assert_eq!(framed.get_pixel(0, 0).0[3], 0);
+    }
+
+    #[test]
+    fn rejects_unknown_frames() {
+        let err = find_device_frame("nokia-3310").err().unwrap();
+        assert!(err.contains("iphone-14-pro"));
+    }
+
+    #[test]
+    fn falls_back_to_session_platform_then_web() {
+        let html = "<html><body></body></html>";
+        assert_eq!(resolve_capture_size(html, Some("mobile")), MOBILE_CAPTURE_SIZE);
+        assert_eq!(resolve_capture_size(html, None), WEB_CAPTURE_SIZE);
+    }
 }
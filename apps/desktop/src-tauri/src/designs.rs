@@ -1,11 +1,14 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::inline_assets::{fetch_assets, inline_assets, remote_assets, AssetCache};
 use crate::state::DesignFile;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
 
 /// Extract an HTML attribute value from content
-fn extract_html_attr(html: &str, attr: &str) -> Option<String> {
+pub(crate) fn extract_html_attr(html: &str, attr: &str) -> Option<String> {
     let pattern = format!(r#"{}=["']([^"']+)["']"#, attr);
     regex::Regex::new(&pattern)
         .ok()?
@@ -14,80 +17,286 @@ fn extract_html_attr(html: &str, attr: &str) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
-#[tauri::command]
-pub fn load_session_designs(session_cwd: String) -> Vec<DesignFile> {
-    let session_dir = PathBuf::from(&session_cwd);
-    let screens_dir = session_dir.join("screens");
-    let mut designs = Vec::new();
+/// Cheap well-formedness check: a closing `</html>` and balanced `<body>` tags.
+/// A substring scan rather than a parser, enough to catch truncated writes.
+pub fn is_well_formed_html(html: &str) -> bool {
+    let lower = html.to_ascii_lowercase();
+    let opens = lower.matches("<body").count();
+    let closes = lower.matches("</body>").count();
+    lower.contains("</html>") && opens == closes
+}
 
-    let mut process_dir = |dir: &PathBuf| {
-        if !dir.exists() {
-            return;
-        }
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().is_some_and(|e| e == "html") {
-                    if let Ok(html) = fs::read_to_string(&path) {
-                        let filename = path
-                            .file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_default();
-
-                        // Skip duplicates
-                        if designs.iter().any(|d: &DesignFile| d.filename == filename) {
-                            continue;
-                        }
-
-                        let title = extract_html_attr(&html, "data-title").unwrap_or_else(|| {
-                            filename
-                                .replace(".html", "")
-                                .split('-')
-                                .map(|w| {
-                                    let mut c = w.chars();
-                                    match c.next() {
-                                        None => String::new(),
-                                        Some(f) => f.to_uppercase().chain(c).collect(),
-                                    }
-                                })
-                                .collect::<Vec<_>>()
-                                .join(" ")
-                        });
-
-                        let screen_type = extract_html_attr(&html, "data-screen-type")
-                            .unwrap_or_else(|| "web".to_string());
-
-                        let modified_at = entry
-                            .metadata()
-                            .and_then(|m| m.modified())
-                            .map(|t| t.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0))
-                            .unwrap_or(0);
-
-                        let violations = crate::screen_validator::validate(&html);
-
-                        designs.push(DesignFile {
-                            filename,
-                            title,
-                            screen_type,
-                            html,
-                            modified_at,
-                            violations,
-                        });
-                    }
+/// Parse a single design file from disk. Returns `None` if it can't be read.
+pub(crate) fn read_design_file(path: &Path) -> Option<DesignFile> {
+    let html = fs::read_to_string(path).ok()?;
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let title = extract_html_attr(&html, "data-title").unwrap_or_else(|| {
+        filename
+            .replace(".html", "")
+            .split('-')
+            .map(|w| {
+                let mut c = w.chars();
+                match c.next() {
+                    None => String::new(),
+                    Some(f) => f.to_uppercase().chain(c).collect(),
                 }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    let screen_type = extract_html_attr(&html, "data-screen-type")
+        .unwrap_or_else(|| "web".to_string());
+
+    let modified_at = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| t.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0))
+        .unwrap_or(0);
+
+    let violations = crate::screen_validator::validate(&html);
+    let valid = is_well_formed_html(&html);
+    let thumbnail_path = crate::thumbnails::cached_thumbnail_path(path)
+        .map(|p| p.to_string_lossy().to_string());
+
+    Some(DesignFile {
+        filename,
+        title,
+        screen_type,
+        html,
+        modified_at,
+        violations,
+        valid,
+        thumbnail_path,
+        folder: None,
+    })
+}
+
+/// Subfolders of `screens/` that can hold a flow's screens, skipping hidden
+/// ones like `.history` and `.thumbnails`
+fn flow_folders(screens_dir: &Path) -> Vec<String> {
+    let mut folders: Vec<String> = fs::read_dir(screens_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+    folders.sort();
+    folders
+}
+
+/// Folders that hold a session's designs, in lookup order: `screens/`, its
+/// flow folders, then the session root
+pub(crate) fn design_dirs(session_dir: &Path) -> Vec<PathBuf> {
+    let screens_dir = session_dir.join("screens");
+    let mut dirs = vec![screens_dir.clone()];
+    dirs.extend(flow_folders(&screens_dir).into_iter().map(|f| screens_dir.join(f)));
+    dirs.push(session_dir.to_path_buf());
+    dirs
+}
+
+/// Every design file in a session: `screens/`, one level of flow folders
+/// under it (no deeper, so stray build output isn't crawled), then the legacy
+/// flat layout in the session root. A file reachable twice (e.g. via a
+/// symlink) is listed once, and a root file shadowed by a same-named file in
+/// `screens/` is skipped, so all callers agree on what a session contains.
+pub(crate) fn resolve_design_files(session_dir: &Path) -> Vec<PathBuf> {
+    let screens_dir = session_dir.join("screens");
+    let mut seen = std::collections::HashSet::new();
+    let mut files = Vec::new();
+    for dir in design_dirs(session_dir) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "html") && p.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            let shadowed = dir == session_dir
+                && path
+                    .file_name()
+                    .is_some_and(|name| screens_dir.join(name).is_file());
+            if !shadowed && seen.insert(canonical) {
+                files.push(path);
             }
         }
-    };
+    }
+    files
+}
+
+/// Flow folder a resolved design file lives in, if it's below `screens/<folder>/`
+pub(crate) fn design_folder(session_dir: &Path, path: &Path) -> Option<String> {
+    let parent = path.parent()?;
+    (parent.parent()? == session_dir.join("screens"))
+        .then(|| parent.file_name().map(|n| n.to_string_lossy().to_string()))
+        .flatten()
+}
 
-    // Scan both session root and screens/ subfolder
-    process_dir(&session_dir);
-    process_dir(&screens_dir);
+#[tauri::command]
+pub fn load_session_designs(session_cwd: String) -> Vec<DesignFile> {
+    let session_dir = PathBuf::from(&session_cwd);
+    let mut designs: Vec<DesignFile> = resolve_design_files(&session_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let mut design = read_design_file(&path)?;
+            design.folder = design_folder(&session_dir, &path);
+            Some(design)
+        })
+        .collect();
 
     // Sort by modified time (oldest first)
-    designs.sort_by(|a, b| a.modified_at.cmp(&b.modified_at));
+    designs.sort_by_key(|d| d.modified_at);
     designs
 }
 
+/// Count designs in a session without reading them; matches what
+/// `load_session_designs` would return
+#[tauri::command]
+pub fn count_session_designs(session_cwd: String) -> u32 {
+    resolve_design_files(Path::new(&session_cwd)).len() as u32
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecentDesign {
+    pub session_id: String,
+    pub session_name: String,
+    pub path: String,
+    pub filename: String,
+    pub title: String,
+    pub screen_type: String,
+    pub modified_at: u64,
+}
+
+/// Newest `limit` designs across the given sessions. Files are ranked by
+/// mtime first so only the ones returned are read.
+fn recent_designs_in(sessions: &[crate::state::SessionMeta], limit: usize) -> Vec<RecentDesign> {
+    let mut candidates: Vec<(std::time::SystemTime, PathBuf, &crate::state::SessionMeta)> = sessions
+        .iter()
+        .flat_map(|session| {
+            resolve_design_files(Path::new(&session.cwd))
+                .into_iter()
+                .filter_map(move |path| {
+                    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                    Some((modified, path, session))
+                })
+        })
+        .collect();
+    candidates.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+
+    candidates
+        .into_iter()
+        .filter_map(|(_, path, session)| {
+            let design = read_design_file(&path)?;
+            Some(RecentDesign {
+                session_id: session.id.clone(),
+                session_name: session.name.clone(),
+                path: path.to_string_lossy().to_string(),
+                filename: design.filename,
+                title: design.title,
+                screen_type: design.screen_type,
+                modified_at: design.modified_at,
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Most recently modified designs across all sessions, newest first
+#[tauri::command]
+pub fn recent_designs(limit: usize) -> Vec<RecentDesign> {
+    recent_designs_in(&crate::sessions::load_sessions_store().sessions, limit)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileProblem {
+    /// Couldn't be read as UTF-8 text
+    Unreadable,
+    /// Zero bytes or only whitespace
+    Empty,
+    /// Missing `</html>` or unbalanced `<body>`
+    Truncated,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileIssue {
+    pub path: String,
+    pub problem: FileProblem,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionIntegrity {
+    pub session_exists: bool,
+    pub screens_dir_missing: bool,
+    pub files_checked: u32,
+    pub issues: Vec<FileIssue>,
+    /// What the user can do about the issues found, if any
+    pub suggestion: Option<String>,
+}
+
+/// Health check for a session dir: every design file is read and checked
+/// for emptiness and truncation, and a missing `screens/` is noted
+#[tauri::command]
+pub fn verify_session(session_cwd: String) -> SessionIntegrity {
+    let session_dir = PathBuf::from(&session_cwd);
+    let session_exists = session_dir.is_dir();
+    let screens_dir_missing = !session_dir.join("screens").is_dir();
+    let files = resolve_design_files(&session_dir);
+
+    let issues: Vec<FileIssue> = files
+        .iter()
+        .filter_map(|path| {
+            let (problem, detail) = match fs::read_to_string(path) {
+                Err(e) => (FileProblem::Unreadable, e.to_string()),
+                Ok(html) if html.trim().is_empty() => {
+                    (FileProblem::Empty, format!("{} bytes", html.len()))
+                }
+                Ok(html) if !is_well_formed_html(&html) => (
+                    FileProblem::Truncated,
+                    "Missing </html> or unbalanced <body>".to_string(),
+                ),
+                Ok(_) => return None,
+            };
+            Some(FileIssue {
+                path: path.to_string_lossy().to_string(),
+                problem,
+                detail,
+            })
+        })
+        .collect();
+
+    let suggestion = if !session_exists {
+        Some("The session folder is gone; delete the session from the list".to_string())
+    } else if !issues.is_empty() {
+        Some("Ask the agent to regenerate the listed screens, or delete them".to_string())
+    } else if screens_dir_missing {
+        Some("Reopen the session to recreate its screens folder".to_string())
+    } else {
+        None
+    };
+
+    SessionIntegrity {
+        session_exists,
+        screens_dir_missing,
+        files_checked: files.len() as u32,
+        issues,
+        suggestion,
+    }
+}
+
 /// Delete a design file from disk
 #[tauri::command]
 pub fn delete_design(file_path: String) -> AppResult<()> {
@@ -99,32 +308,1281 @@ pub fn delete_design(file_path: String) -> AppResult<()> {
     Ok(())
 }
 
-/// Copy all design files from one session to another
+/// Copy all design files from one session into another's `screens/`,
+/// keeping flow folders. Root-level files from the flat layout land in `screens/`.
 #[tauri::command]
-pub fn copy_session_designs(source_cwd: String, dest_cwd: String) -> AppResult<u32> {
-    let source_screens = PathBuf::from(&source_cwd).join("screens");
-    let dest_screens = PathBuf::from(&dest_cwd).join("screens");
+pub fn copy_session_designs(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::state::AppState>,
+    source_cwd: String,
+    dest_cwd: String,
+) -> AppResult<u32> {
+    // One `designs:reloaded` for the destination instead of an event per file
+    let paused = crate::watcher::pause_watch(&state, &dest_cwd);
+    let result = copy_designs_between(&source_cwd, &dest_cwd);
+    if paused {
+        crate::watcher::resume_watch(&app, &state, &dest_cwd);
+    }
+    result
+}
+
+fn copy_designs_between(source_cwd: &str, dest_cwd: &str) -> AppResult<u32> {
+    let source_dir = PathBuf::from(source_cwd);
+    let dest_screens = PathBuf::from(dest_cwd).join("screens");
 
     // Create destination screens directory
     fs::create_dir_all(&dest_screens).map_err(|e| format!("Failed to create screens dir: {}", e))?;
 
     let mut copied = 0u32;
+    for path in resolve_design_files(&source_dir) {
+        let Some(filename) = path.file_name() else {
+            continue;
+        };
+        let dest_dir = match design_folder(&source_dir, &path) {
+            Some(folder) => {
+                let dir = dest_screens.join(folder);
+                fs::create_dir_all(&dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                dir
+            }
+            None => dest_screens.clone(),
+        };
+        fs::copy(&path, dest_dir.join(filename))
+            .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        copied += 1;
+    }
+
+    Ok(copied)
+}
+
+/// First free `name.html`, `name-2.html`, `name-3.html`, ... in `dir`
+fn unique_design_path(dir: &Path, filename: &str) -> PathBuf {
+    let candidate = dir.join(filename);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let stem = Path::new(filename)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (2..)
+        .map(|n| dir.join(format!("{}-{}.html", stem, n)))
+        .find(|p| !p.exists())
+        .expect("unbounded range always yields a free name")
+}
+
+/// Copy a single design into another session's `screens/`, renaming on
+/// collision. Returns the path of the new file.
+#[tauri::command]
+pub fn copy_design(source_file_path: String, dest_session_cwd: String) -> AppResult<String> {
+    let source = PathBuf::from(&source_file_path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", source_file_path).into());
+    }
+    if source.extension().is_none_or(|e| e != "html") {
+        return Err(format!("Not an HTML design: {}", source_file_path).into());
+    }
+    let filename = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let dest_screens = PathBuf::from(&dest_session_cwd).join("screens");
+    fs::create_dir_all(&dest_screens).map_err(|e| format!("Failed to create screens dir: {}", e))?;
+
+    let dest_path = unique_design_path(&dest_screens, &filename);
+    fs::copy(&source, &dest_path)
+        .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+
+    Ok(dest_path.to_string_lossy().to_string())
+}
+
+/// Fork a design next to itself as `<name>-copy.html` (then `-copy-2`, ...).
+/// Returns the path of the new file.
+#[tauri::command]
+pub fn duplicate_design(file_path: String) -> AppResult<String> {
+    let source = PathBuf::from(&file_path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", file_path).into());
+    }
+    if source.extension().is_none_or(|e| e != "html") {
+        return Err(format!("Not an HTML design: {}", file_path).into());
+    }
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = source.parent().unwrap_or(Path::new("."));
+
+    let dest_path = unique_design_path(dir, &format!("{}-copy.html", stem));
+    fs::copy(&source, &dest_path)
+        .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+
+    Ok(dest_path.to_string_lossy().to_string())
+}
+
+/// Lowercase hex SHA-256 of a design's contents, so previews can be keyed by
+/// content rather than mtime (which copies don't preserve)
+#[tauri::command]
+pub fn design_hash(file_path: String) -> AppResult<String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    Ok(content_hash(&bytes))
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SessionComparison {
+    pub identical: Vec<String>,
+    pub different: Vec<String>,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+}
+
+/// Content hashes of a session's design files, keyed by `<folder>/<filename>`
+/// for flow screens and plain `<filename>` otherwise (root or `screens/`)
+fn screen_hashes(session_cwd: &str) -> std::collections::BTreeMap<String, String> {
+    let session_dir = Path::new(session_cwd);
+    resolve_design_files(session_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let bytes = fs::read(&path).ok()?;
+            let filename = path.file_name()?.to_string_lossy().to_string();
+            let key = match design_folder(session_dir, &path) {
+                Some(folder) => format!("{}/{}", folder, filename),
+                None => filename,
+            };
+            Some((key, content_hash(&bytes)))
+        })
+        .collect()
+}
+
+/// Match screens by path within the session across two sessions (e.g. a fork
+/// and its original) and report which are identical, which differ, and which
+/// exist on one side only
+#[tauri::command]
+pub fn compare_sessions(session_a_cwd: String, session_b_cwd: String) -> SessionComparison {
+    let a = screen_hashes(&session_a_cwd);
+    let mut b = screen_hashes(&session_b_cwd);
+    let mut comparison = SessionComparison::default();
+
+    for (name, hash_a) in a {
+        match b.remove(&name) {
+            Some(hash_b) if hash_b == hash_a => comparison.identical.push(name),
+            Some(_) => comparison.different.push(name),
+            None => comparison.only_in_a.push(name),
+        }
+    }
+    comparison.only_in_b = b.into_keys().collect();
+    comparison
+}
+
+/// Save a design's HTML to a user-chosen path, appending `.html` if missing.
+/// Returns the path written.
+#[tauri::command]
+pub fn export_design_html(file_path: String, dest_path: String) -> AppResult<String> {
+    let source = PathBuf::from(&file_path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", file_path).into());
+    }
+
+    let dest = with_html_extension(&dest_path);
+    fs::copy(&source, &dest).map_err(|e| format!("Failed to export to {}: {}", dest.display(), e))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct FigmaManifestScreen {
+    file: String,
+    title: String,
+    screen_type: String,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FigmaBundle {
+    pub path: String,
+    pub screen_count: u32,
+    /// Assets that couldn't be inlined, so the screens still load them over
+    /// the network
+    pub remote_assets: Vec<String>,
+}
+
+/// Lowercase, dash-separated file stem for a screen title
+fn slugify(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "screen".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Cap on screens per bundle export, matching `MAX_IMAGE_EXPORT_SCREENS` in
+/// the frontend. Each screen is held in memory while the output is built.
+pub(crate) const MAX_EXPORT_SCREENS: usize = 100;
+
+/// Shared pre-flight check for the export commands: refuse an empty export
+/// and one over `MAX_EXPORT_SCREENS`, naming the limit
+fn check_export_size(count: usize) -> AppResult<()> {
+    if count == 0 {
+        return Err("No screens to export".into());
+    }
+    if count > MAX_EXPORT_SCREENS {
+        return Err(format!(
+            "Too many screens ({}): export is limited to {}. Select fewer screens and export in batches.",
+            count, MAX_EXPORT_SCREENS
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// A screen as a standalone document with its remote assets embedded from
+/// `assets` (see `inline_assets`)
+fn export_document(design: &DesignFile, assets: &AssetCache) -> String {
+    inline_assets(&wrap_design_fragment(&design.html), assets)
+}
+
+/// Fetch everything the given screens load from the network, once per export
+async fn fetch_design_assets<'a>(designs: impl IntoIterator<Item = &'a DesignFile>) -> AssetCache {
+    let documents: Vec<String> = designs
+        .into_iter()
+        .map(|design| wrap_design_fragment(&design.html))
+        .collect();
+    fetch_assets(&documents).await
+}
+
+/// Zip a session's screens for HTML-to-Figma importers: each screen as a
+/// standalone document under `screens/` plus a `manifest.json` with titles,
+/// types and frame sizes. Appends `.zip` to `dest_path` if missing.
+///
+/// Tailwind, Iconify icons, web fonts and images are downloaded and inlined
+/// so the screens render without a connection. Anything that couldn't be
+/// fetched stays linked and is listed in `remote_assets`, both in the result
+/// and in the manifest.
+#[tauri::command]
+pub async fn export_figma_bundle(session_cwd: String, dest_path: String) -> AppResult<FigmaBundle> {
+    let designs = load_session_designs(session_cwd);
+    check_export_size(designs.len())?;
+    let assets = fetch_design_assets(&designs).await;
+    write_figma_bundle(&designs, &dest_path, &assets)
+}
+
+fn write_figma_bundle(
+    designs: &[DesignFile],
+    dest_path: &str,
+    assets: &AssetCache,
+) -> AppResult<FigmaBundle> {
+    use std::io::Write;
+
+    let mut dest = PathBuf::from(dest_path);
+    if !dest.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip")) {
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".zip");
+        dest.set_file_name(name);
+    }
+
+    let zip_err = |e: zip::result::ZipError| format!("Failed to write bundle: {}", e);
+    let mut writer = zip::ZipWriter::new(fs::File::create(&dest)?);
+    let options = zip::write::SimpleFileOptions::default();
+    let mut used = std::collections::HashSet::new();
+    let mut manifest = Vec::with_capacity(designs.len());
+    let mut remote = std::collections::BTreeSet::new();
+
+    for design in designs {
+        let slug = slugify(&design.title);
+        let file = (1..)
+            .map(|n| match n {
+                1 => format!("screens/{}.html", slug),
+                n => format!("screens/{}-{}.html", slug, n),
+            })
+            .find(|f| !used.contains(f))
+            .expect("unbounded range always yields a free name");
+        used.insert(file.clone());
+
+        let document = export_document(design, assets);
+        writer.start_file(file.as_str(), options).map_err(zip_err)?;
+        writer.write_all(document.as_bytes())?;
+        remote.extend(remote_assets(&document));
+
+        let (width, height) = crate::capture::default_capture_size(&design.screen_type);
+        manifest.push(FigmaManifestScreen {
+            file,
+            title: design.title.clone(),
+            screen_type: design.screen_type.clone(),
+            width,
+            height,
+        });
+    }
+
+    let remote_assets: Vec<String> = remote.into_iter().collect();
+    writer.start_file("manifest.json", options).map_err(zip_err)?;
+    writer.write_all(
+        serde_json::to_string_pretty(
+            &serde_json::json!({ "screens": manifest, "remote_assets": remote_assets }),
+        )?
+        .as_bytes(),
+    )?;
+    writer.finish().map_err(zip_err)?;
+
+    Ok(FigmaBundle {
+        path: dest.to_string_lossy().to_string(),
+        screen_count: designs.len() as u32,
+        remote_assets,
+    })
+}
+
+/// Escape text for use inside a double-quoted HTML attribute
+fn escape_html_attr(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Deck shell for `export_prototype`; `{{SLIDES}}` is replaced with one
+/// `<section>` per screen
+const PROTOTYPE_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{{TITLE}}</title>
+  <style>
+    html, body { margin: 0; height: 100%; background: #0b0d10; color: #e5e7eb; font-family: system-ui, sans-serif; }
+    main { height: calc(100% - 56px); display: flex; align-items: center; justify-content: center; overflow: auto; }
+    section { display: none; flex-direction: column; align-items: center; gap: 12px; padding: 16px; }
+    section.active { display: flex; }
+    iframe { border: 0; border-radius: 8px; background: #fff; box-shadow: 0 8px 32px rgba(0, 0, 0, 0.4); }
+    nav { height: 56px; display: flex; align-items: center; justify-content: center; gap: 16px; }
+    button { background: #1f2937; color: inherit; border: 0; border-radius: 6px; padding: 8px 14px; cursor: pointer; }
+    button:disabled { opacity: 0.4; cursor: default; }
+  </style>
+</head>
+<body>
+  <main>
+{{SLIDES}}
+  </main>
+  <nav>
+    <button id="prev">&larr; Prev</button>
+    <span id="counter"></span>
+    <button id="next">Next &rarr;</button>
+  </nav>
+  <script>
+    const slides = document.querySelectorAll("section");
+    let current = 0;
+    function show(index) {
+      current = Math.max(0, Math.min(slides.length - 1, index));
+      slides.forEach((s, i) => s.classList.toggle("active", i === current));
+      document.getElementById("counter").textContent = `${current + 1} / ${slides.length}`;
+      document.getElementById("prev").disabled = current === 0;
+      document.getElementById("next").disabled = current === slides.length - 1;
+    }
+    document.getElementById("prev").onclick = () => show(current - 1);
+    document.getElementById("next").onclick = () => show(current + 1);
+    document.addEventListener("keydown", (e) => {
+      if (e.key === "ArrowLeft") show(current - 1);
+      if (e.key === "ArrowRight") show(current + 1);
+    });
+    show(0);
+  </script>
+</body>
+</html>
+"#;
+
+#[derive(Debug, Serialize)]
+pub struct PrototypeExport {
+    pub path: String,
+    pub screen_count: u32,
+    /// Assets that couldn't be inlined, so the deck still loads them over the
+    /// network
+    pub remote_assets: Vec<String>,
+}
+
+/// Bundle screens into one HTML file with prev/next navigation (buttons and
+/// arrow keys). `order` lists screen filenames, or `folder/filename` for
+/// screens in a flow folder; an empty list uses every screen. Each screen is
+/// embedded via `srcdoc` and sized by its screen type. Appends `.html` to
+/// `dest_path` if missing.
+///
+/// Tailwind, Iconify icons, web fonts and images are downloaded and inlined
+/// into each screen, so the deck works offline as one file. Anything that
+/// couldn't be fetched stays linked and is returned in `remote_assets`.
+#[tauri::command]
+pub async fn export_prototype(
+    session_cwd: String,
+    dest_path: String,
+    order: Vec<String>,
+) -> AppResult<PrototypeExport> {
+    let designs = load_session_designs(session_cwd);
+    let key = |d: &DesignFile| match &d.folder {
+        Some(folder) => format!("{}/{}", folder, d.filename),
+        None => d.filename.clone(),
+    };
+
+    let screens: Vec<&DesignFile> = if order.is_empty() {
+        designs.iter().collect()
+    } else {
+        order
+            .iter()
+            .map(|name| {
+                designs
+                    .iter()
+                    .find(|d| key(d) == *name)
+                    .ok_or_else(|| AppError::Custom(format!("Screen not found: {}", name)))
+            })
+            .collect::<AppResult<_>>()?
+    };
+    check_export_size(screens.len())?;
+    let assets = fetch_design_assets(screens.iter().copied()).await;
+
+    let documents: Vec<String> = screens.iter().map(|d| export_document(d, &assets)).collect();
+    let slides: String = screens
+        .iter()
+        .zip(&documents)
+        .map(|(design, document)| {
+            format!(
+                "    <section>\n      <div>{}</div>\n      {}\n    </section>\n",
+                escape_html_attr(&design.title),
+                screen_iframe(design, document, "")
+            )
+        })
+        .collect();
+
+    let dest = with_html_extension(&dest_path);
+    let deck = PROTOTYPE_TEMPLATE
+        .replace("{{TITLE}}", &escape_html_attr(&file_stem_or(&dest, "Prototype")))
+        .replace("{{SLIDES}}", slides.trim_end());
+    fs::write(&dest, deck).map_err(|e| format!("Failed to export to {}: {}", dest.display(), e))?;
+    Ok(PrototypeExport {
+        path: dest.to_string_lossy().to_string(),
+        screen_count: screens.len() as u32,
+        remote_assets: documents_remote_assets(&documents),
+    })
+}
+
+/// `dest_path` with `.html` appended if it has another or no extension
+fn with_html_extension(dest_path: &str) -> PathBuf {
+    let mut dest = PathBuf::from(dest_path);
+    if !dest.extension().is_some_and(|e| e.eq_ignore_ascii_case("html")) {
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".html");
+        dest.set_file_name(name);
+    }
+    dest
+}
 
-    if source_screens.exists() {
-        if let Ok(entries) = fs::read_dir(&source_screens) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().is_some_and(|e| e == "html") {
-                    if let Some(filename) = path.file_name() {
-                        let dest_path = dest_screens.join(filename);
-                        fs::copy(&path, &dest_path)
-                            .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
-                        copied += 1;
-                    }
+fn file_stem_or(path: &Path, fallback: &str) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Remote assets left in exported screen documents, without repeats
+fn documents_remote_assets(documents: &[String]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for document in documents {
+        for url in remote_assets(document) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+/// A screen's `document` embedded as a `srcdoc` iframe at its screen type's
+/// native size
+fn screen_iframe(design: &DesignFile, document: &str, attrs: &str) -> String {
+    let (width, height) = crate::capture::default_capture_size(&design.screen_type);
+    format!(
+        "<iframe title=\"{}\" width=\"{}\" height=\"{}\"{} srcdoc=\"{}\"></iframe>",
+        escape_html_attr(&design.title),
+        width,
+        height,
+        attrs,
+        escape_html_attr(document)
+    )
+}
+
+/// Gallery thumbnails are the screens at their native size scaled down by this
+const GALLERY_SCALE: f64 = 0.4;
+
+/// Page shell for `export_gallery_html`; `{{SCREENS}}` gets one `<figure>` per screen
+const GALLERY_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{{TITLE}}</title>
+  <style>
+    body { margin: 0; padding: 32px; background: #f4f4f5; color: #18181b; font-family: system-ui, sans-serif; }
+    h1 { margin: 0 0 24px; font-size: 20px; font-weight: 600; }
+    main { display: flex; flex-wrap: wrap; gap: 32px; align-items: flex-start; }
+    figure { margin: 0; }
+    figcaption { margin-top: 8px; font-size: 13px; color: #52525b; }
+    .frame { overflow: hidden; border-radius: 8px; background: #fff; box-shadow: 0 1px 3px rgba(0, 0, 0, 0.12); }
+    .frame iframe { border: 0; transform-origin: 0 0; pointer-events: none; }
+    .frame:hover iframe { pointer-events: auto; }
+  </style>
+</head>
+<body>
+  <h1>{{TITLE}}</h1>
+  <main>
+{{SCREENS}}
+  </main>
+</body>
+</html>
+"#;
+
+#[derive(Debug, Serialize)]
+pub struct GalleryExport {
+    pub path: String,
+    pub screen_count: u32,
+    /// Assets that couldn't be inlined, so the page still loads them over the
+    /// network
+    pub remote_assets: Vec<String>,
+}
+
+/// Write every screen of a session into one HTML page: a grid of scaled-down
+/// `srcdoc` iframes sized by screen type, each labelled with its title.
+/// Appends `.html` to `dest_path` if missing. Tailwind, Iconify icons, web
+/// fonts and images are downloaded and inlined into each screen, so the page
+/// needs no server or network. Anything that couldn't be fetched stays linked
+/// and is returned in `remote_assets`.
+#[tauri::command]
+pub async fn export_gallery_html(
+    session_cwd: String,
+    dest_path: String,
+) -> AppResult<GalleryExport> {
+    let designs = load_session_designs(session_cwd);
+    check_export_size(designs.len())?;
+    let assets = fetch_design_assets(&designs).await;
+
+    let documents: Vec<String> = designs.iter().map(|d| export_document(d, &assets)).collect();
+    let figures: String = designs
+        .iter()
+        .zip(&documents)
+        .map(|(design, document)| {
+            let (width, height) = crate::capture::default_capture_size(&design.screen_type);
+            let style = format!(" style=\"transform: scale({})\"", GALLERY_SCALE);
+            format!(
+                "    <figure>\n      <div class=\"frame\" style=\"width: {}px; height: {}px\">{}</div>\n      <figcaption>{}</figcaption>\n    </figure>\n",
+                (f64::from(width) * GALLERY_SCALE).round(),
+                (f64::from(height) * GALLERY_SCALE).round(),
+                screen_iframe(design, document, &style),
+                escape_html_attr(&design.title)
+            )
+        })
+        .collect();
+
+    let dest = with_html_extension(&dest_path);
+    let page = GALLERY_TEMPLATE
+        .replace("{{TITLE}}", &escape_html_attr(&file_stem_or(&dest, "Gallery")))
+        .replace("{{SCREENS}}", figures.trim_end());
+    fs::write(&dest, page).map_err(|e| format!("Failed to export to {}: {}", dest.display(), e))?;
+
+    Ok(GalleryExport {
+        path: dest.to_string_lossy().to_string(),
+        screen_count: designs.len() as u32,
+        remote_assets: documents_remote_assets(&documents),
+    })
+}
+
+/// `--name: value` declarations from a screen's `@theme` blocks (including
+/// ones with modifiers like `@theme inline`), in source order. A name
+/// declared twice keeps its last value, as in CSS.
+fn parse_theme_tokens(html: &str) -> Vec<(String, String)> {
+    let block_re = regex::Regex::new(r"(?s)@theme[^{;]*\{(.*?)\}").expect("valid regex");
+    let mut tokens: Vec<(String, String)> = Vec::new();
+    for block in block_re.captures_iter(html) {
+        for declaration in block[1].split(';') {
+            let Some((name, value)) = declaration.split_once(':') else {
+                continue;
+            };
+            let Some(name) = name.trim().strip_prefix("--") else {
+                continue;
+            };
+            let value = value.trim();
+            if name.is_empty() || value.is_empty() {
+                continue;
+            }
+            match tokens.iter_mut().find(|(n, _)| n == name) {
+                Some(existing) => existing.1 = value.to_string(),
+                None => tokens.push((name.to_string(), value.to_string())),
+            }
+        }
+    }
+    tokens
+}
+
+/// Design-token `$type` for a Tailwind theme namespace
+fn token_type(group: &str) -> Option<&'static str> {
+    match group {
+        "color" => Some("color"),
+        "font" => Some("fontFamily"),
+        "radius" | "spacing" | "text" => Some("dimension"),
+        "shadow" => Some("shadow"),
+        _ => None,
+    }
+}
+
+/// JSON key for a namespace's bare value (`--spacing` -> `spacing.DEFAULT`),
+/// so it can sit next to the namespace's other tokens
+const DEFAULT_TOKEN_KEY: &str = "DEFAULT";
+
+/// Render tokens as a `:root` CSS block or a W3C design-token style JSON
+/// map grouped by namespace (`--color-card` -> `color.card`)
+fn render_design_tokens(tokens: &[(String, String)], format: &str) -> AppResult<String> {
+    match format {
+        "css" => {
+            let mut css = String::from(":root {\n");
+            for (name, value) in tokens {
+                css.push_str(&format!("  --{}: {};\n", name, value));
+            }
+            css.push_str("}\n");
+            Ok(css)
+        }
+        "json" => {
+            let mut root = serde_json::Map::new();
+            for (name, value) in tokens {
+                let (group, key) = name.split_once('-').unwrap_or((name.as_str(), ""));
+                let mut token = serde_json::Map::new();
+                token.insert("$value".to_string(), serde_json::Value::from(value.as_str()));
+                if let Some(kind) = token_type(group) {
+                    token.insert("$type".to_string(), serde_json::Value::from(kind));
+                }
+                let key = if key.is_empty() { DEFAULT_TOKEN_KEY } else { key };
+                if let serde_json::Value::Object(entries) = root
+                    .entry(group.to_string())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+                {
+                    entries.insert(key.to_string(), serde_json::Value::Object(token));
                 }
             }
+            Ok(serde_json::to_string_pretty(&root)? + "\n")
         }
+        other => Err(format!("Unsupported token format: {} (expected css or json)", other).into()),
     }
+}
 
-    Ok(copied)
+/// Export a screen's `@theme` tokens as CSS custom properties (`format` =
+/// `"css"`) or a design-token JSON map (`"json"`)
+#[tauri::command]
+pub fn export_design_tokens(file_path: String, format: String) -> AppResult<String> {
+    let html = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let tokens = parse_theme_tokens(&html);
+    if tokens.is_empty() {
+        return Err("No @theme tokens found in this screen".into());
+    }
+    render_design_tokens(&tokens, &format)
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenConflict {
+    pub name: String,
+    /// `(filename, value)` for each screen declaring the token
+    pub values: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionDesignTokens {
+    pub content: String,
+    pub conflicts: Vec<TokenConflict>,
+}
+
+/// Merge every screen's `@theme` tokens into one export. When screens
+/// disagree, the oldest screen's value is used and the token is reported in
+/// `conflicts`.
+#[tauri::command]
+pub fn export_session_design_tokens(session_cwd: String, format: String) -> AppResult<SessionDesignTokens> {
+    let mut merged: Vec<(String, String)> = Vec::new();
+    let mut declared: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for design in load_session_designs(session_cwd) {
+        for (name, value) in parse_theme_tokens(&design.html) {
+            match declared.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, values)) => values.push((design.filename.clone(), value)),
+                None => {
+                    merged.push((name.clone(), value.clone()));
+                    declared.push((name, vec![(design.filename.clone(), value)]));
+                }
+            }
+        }
+    }
+    if merged.is_empty() {
+        return Err("No @theme tokens found in this session".into());
+    }
+
+    let conflicts = declared
+        .into_iter()
+        .filter(|(_, values)| values.iter().any(|(_, v)| *v != values[0].1))
+        .map(|(name, values)| TokenConflict { name, values })
+        .collect();
+    Ok(SessionDesignTokens {
+        content: render_design_tokens(&merged, &format)?,
+        conflicts,
+    })
+}
+
+/// Scripts every generated screen loads; mirrors the template in the designer prompts
+const DOCUMENT_HEAD_SCRIPTS: &str = r#"  <script src="https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4"></script>
+  <script src="https://code.iconify.design/3/3.1.0/iconify.min.js"></script>"#;
+
+/// Wrap a `<body>` fragment in the standard screen document, hoisting any
+/// `text/tailwindcss` style blocks (the `@theme` block) into `<head>`.
+/// Full documents are returned unchanged.
+fn wrap_design_fragment(html: &str) -> String {
+    let lower = html.trim_start().to_ascii_lowercase();
+    if lower.starts_with("<!doctype html") || lower.contains("<html") {
+        return html.to_string();
+    }
+
+    let style_re = regex::Regex::new(
+        r#"(?is)<style[^>]*type=["']text/tailwindcss["'][^>]*>.*?</style>"#,
+    )
+    .expect("valid regex");
+    let styles: Vec<&str> = style_re.find_iter(html).map(|m| m.as_str()).collect();
+    let body = style_re.replace_all(html, "");
+    let body = body.trim();
+
+    let title = extract_html_attr(html, "data-title").unwrap_or_else(|| "Screen".to_string());
+    let viewport = match extract_html_attr(html, "data-screen-type").as_deref() {
+        Some("mobile") => "width=393, initial-scale=1.0",
+        _ => "width=device-width, initial-scale=1.0",
+    };
+
+    let mut head = format!(
+        "  <meta charset=\"UTF-8\">\n  <meta name=\"viewport\" content=\"{}\">\n{}\n",
+        viewport, DOCUMENT_HEAD_SCRIPTS
+    );
+    for style in styles {
+        head.push_str("  ");
+        head.push_str(style);
+        head.push('\n');
+    }
+
+    let body = if body.to_ascii_lowercase().starts_with("<body") {
+        body.to_string()
+    } else {
+        format!("<body>\n{}\n</body>", body)
+    };
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\" data-title=\"{}\">\n<head>\n{}</head>\n{}\n</html>\n",
+        title, head, body
+    )
+}
+
+/// Read a design as a standalone document for copying. CDN links are kept as-is.
+#[tauri::command]
+pub fn get_design_for_clipboard(file_path: String) -> AppResult<String> {
+    let html = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    Ok(wrap_design_fragment(&html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPLETE: &str = "<!DOCTYPE html><html><body><h1>Home</h1></body></html>";
+
+    #[test]
+    fn complete_document_is_well_formed() {
+        assert!(is_well_formed_html(COMPLETE));
+    }
+
+    #[test]
+    fn truncated_document_is_not_well_formed() {
+        assert!(!is_well_formed_html("<!DOCTYPE html><html><body><h1>Ho"));
+        assert!(!is_well_formed_html("<html><body><div></div></html>"));
+    }
+
+    #[test]
+    fn load_session_designs_flags_truncated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(screens.join("home.html"), COMPLETE).unwrap();
+        fs::write(screens.join("profile.html"), &COMPLETE[..30]).unwrap();
+
+        let designs = load_session_designs(dir.path().to_string_lossy().to_string());
+        let valid = |name: &str| designs.iter().find(|d| d.filename == name).unwrap().valid;
+        assert!(valid("home.html"));
+        assert!(!valid("profile.html"));
+    }
+
+    #[test]
+    fn load_session_designs_tags_flow_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(screens.join("onboarding/nested")).unwrap();
+        fs::create_dir_all(screens.join(".thumbnails")).unwrap();
+        fs::write(screens.join("home.html"), COMPLETE).unwrap();
+        fs::write(screens.join("onboarding/a.html"), COMPLETE).unwrap();
+        fs::write(screens.join("onboarding/nested/deep.html"), COMPLETE).unwrap();
+        fs::write(screens.join(".thumbnails/hidden.html"), COMPLETE).unwrap();
+
+        let designs = load_session_designs(dir.path().to_string_lossy().to_string());
+        let mut found: Vec<_> = designs
+            .iter()
+            .map(|d| (d.folder.as_deref(), d.filename.as_str()))
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![(None, "home.html"), (Some("onboarding"), "a.html")]);
+    }
+
+    #[test]
+    fn resolve_design_files_prefers_screens_and_dedupes() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(screens.join("onboarding")).unwrap();
+        fs::write(dir.path().join("home.html"), "<html>old</html>").unwrap();
+        fs::write(screens.join("home.html"), COMPLETE).unwrap();
+        fs::write(screens.join("onboarding/home.html"), COMPLETE).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(screens.join("home.html"), dir.path().join("alias.html")).unwrap();
+
+        let files = resolve_design_files(dir.path());
+        assert_eq!(
+            files,
+            vec![screens.join("home.html"), screens.join("onboarding/home.html")]
+        );
+        assert_eq!(count_session_designs(dir.path().to_string_lossy().to_string()), 2);
+
+        let designs = load_session_designs(dir.path().to_string_lossy().to_string());
+        let root_home = designs.iter().find(|d| d.folder.is_none()).unwrap();
+        assert_eq!(root_home.html, COMPLETE);
+    }
+
+    #[test]
+    fn recent_designs_interleave_sessions_by_mtime() {
+        use std::time::{Duration, SystemTime};
+
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        let base = SystemTime::now() - Duration::from_secs(3600);
+        let write = |dir: &Path, name: &str, minutes: u64| {
+            let screens = dir.join("screens");
+            fs::create_dir_all(&screens).unwrap();
+            let path = screens.join(name);
+            fs::write(&path, COMPLETE).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(base + Duration::from_secs(minutes * 60))
+                .unwrap();
+        };
+        write(a.path(), "a1.html", 1);
+        write(b.path(), "b1.html", 2);
+        write(a.path(), "a2.html", 3);
+        write(b.path(), "b2.html", 4);
+
+        let session = |id: &str, dir: &tempfile::TempDir| crate::state::SessionMeta {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            created_at: String::new(),
+            cwd: dir.path().to_string_lossy().to_string(),
+            platform: None,
+            favorite: false,
+            opencode_session_id: None,
+        };
+        let recent = recent_designs_in(&[session("a", &a), session("b", &b)], 3);
+        let order: Vec<_> = recent
+            .iter()
+            .map(|d| (d.session_id.as_str(), d.filename.as_str()))
+            .collect();
+        assert_eq!(order, vec![("b", "b2.html"), ("a", "a2.html"), ("b", "b1.html")]);
+    }
+
+    #[test]
+    fn verify_session_reports_empty_and_truncated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(screens.join("good.html"), COMPLETE).unwrap();
+        fs::write(screens.join("empty.html"), "").unwrap();
+        fs::write(screens.join("cut.html"), &COMPLETE[..30]).unwrap();
+        fs::write(screens.join("binary.html"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let report = verify_session(dir.path().to_string_lossy().to_string());
+        assert!(report.session_exists);
+        assert!(!report.screens_dir_missing);
+        assert_eq!(report.files_checked, 4);
+        let problem = |name: &str| {
+            report
+                .issues
+                .iter()
+                .find(|i| i.path.ends_with(name))
+                .map(|i| i.problem)
+        };
+        assert_eq!(problem("good.html"), None);
+        assert_eq!(problem("empty.html"), Some(FileProblem::Empty));
+        assert_eq!(problem("cut.html"), Some(FileProblem::Truncated));
+        assert_eq!(problem("binary.html"), Some(FileProblem::Unreadable));
+        assert!(report.suggestion.is_some());
+
+        let bare = tempfile::tempdir().unwrap();
+        let report = verify_session(bare.path().to_string_lossy().to_string());
+        assert!(report.screens_dir_missing);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn count_session_designs_counts_html_in_root_and_screens() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(dir.path().join("landing.html"), COMPLETE).unwrap();
+        fs::write(screens.join("home.html"), COMPLETE).unwrap();
+        fs::write(screens.join("profile.html"), COMPLETE).unwrap();
+        fs::write(screens.join("notes.md"), "not a design").unwrap();
+
+        assert_eq!(count_session_designs(dir.path().to_string_lossy().to_string()), 3);
+    }
+
+    #[test]
+    fn copy_design_renames_on_collision() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let source_file = source.path().join("home.html");
+        fs::write(&source_file, COMPLETE).unwrap();
+        let source_path = source_file.to_string_lossy().to_string();
+        let dest_cwd = dest.path().to_string_lossy().to_string();
+
+        let first = copy_design(source_path.clone(), dest_cwd.clone()).unwrap();
+        let second = copy_design(source_path.clone(), dest_cwd.clone()).unwrap();
+        let third = copy_design(source_path, dest_cwd).unwrap();
+
+        let screens = dest.path().join("screens");
+        assert_eq!(PathBuf::from(first), screens.join("home.html"));
+        assert_eq!(PathBuf::from(second), screens.join("home-2.html"));
+        assert_eq!(PathBuf::from(third), screens.join("home-3.html"));
+        assert_eq!(fs::read_to_string(screens.join("home-2.html")).unwrap(), COMPLETE);
+    }
+
+    #[test]
+    fn design_hash_tracks_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            design_hash(path.to_string_lossy().to_string()).unwrap()
+        };
+
+        let a = hash("a.html", COMPLETE);
+        assert_eq!(a, hash("b.html", COMPLETE));
+        assert_ne!(a, hash("c.html", &COMPLETE.replacen('>', "!", 1)));
+        assert_eq!(a.len(), 64);
+        assert_eq!(a, a.to_lowercase());
+    }
+
+    #[test]
+    fn duplicate_design_increments_suffix() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("home.html");
+        fs::write(&file, COMPLETE).unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let first = duplicate_design(path.clone()).unwrap();
+        let second = duplicate_design(path).unwrap();
+
+        assert_eq!(PathBuf::from(&first), dir.path().join("home-copy.html"));
+        assert_eq!(PathBuf::from(second), dir.path().join("home-copy-2.html"));
+        assert_eq!(fs::read_to_string(first).unwrap(), COMPLETE);
+    }
+
+    #[test]
+    fn copy_design_rejects_non_html() {
+        let source = tempfile::tempdir().unwrap();
+        let file = source.path().join("notes.txt");
+        fs::write(&file, "hi").unwrap();
+        let result = copy_design(
+            file.to_string_lossy().to_string(),
+            source.path().to_string_lossy().to_string(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn exports_refuse_sessions_over_the_screen_cap() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        for i in 0..=MAX_EXPORT_SCREENS {
+            fs::write(screens.join(format!("s{}.html", i)), "<html></html>").unwrap();
+        }
+        let cwd = session.path().to_string_lossy().to_string();
+        let dest = session.path().join("out").to_string_lossy().to_string();
+
+        let gallery = export_gallery_html(cwd.clone(), dest.clone());
+        let err = tauri::async_runtime::block_on(gallery).unwrap_err();
+        assert!(err.to_string().contains(&MAX_EXPORT_SCREENS.to_string()));
+        let figma = tauri::async_runtime::block_on(export_figma_bundle(cwd.clone(), dest.clone()));
+        assert!(figma.is_err());
+        assert!(tauri::async_runtime::block_on(export_prototype(cwd, dest, Vec::new())).is_err());
+        assert!(!session.path().join("out.html").exists());
+        assert!(check_export_size(MAX_EXPORT_SCREENS).is_ok());
+    }
+
+    #[test]
+    fn figma_bundle_has_unique_screens_and_manifest() {
+        use std::io::Read;
+
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        let screen = |title: &str, kind: &str| {
+            format!(
+                r#"<!DOCTYPE html><html data-title="{}" data-screen-type="{}"><body></body></html>"#,
+                title, kind
+            )
+        };
+        fs::write(screens.join("a.html"), screen("Home", "mobile")).unwrap();
+        fs::write(screens.join("b.html"), screen("Home", "web")).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let designs = load_session_designs(session.path().to_string_lossy().to_string());
+        let dest = out.path().join("bundle").to_string_lossy().to_string();
+        let bundle = write_figma_bundle(&designs, &dest, &AssetCache::new()).unwrap();
+        assert_eq!(bundle.screen_count, 2);
+        assert!(bundle.path.ends_with("bundle.zip"));
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&bundle.path).unwrap()).unwrap();
+        assert!(archive.by_name("screens/home.html").is_ok());
+        assert!(archive.by_name("screens/home-2.html").is_ok());
+        let mut manifest = String::new();
+        archive.by_name("manifest.json").unwrap().read_to_string(&mut manifest).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        let sizes: Vec<_> = manifest["screens"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| (s["screen_type"].as_str().unwrap().to_string(), s["width"].as_u64().unwrap()))
+            .collect();
+        assert!(sizes.contains(&("mobile".to_string(), 393)));
+        assert!(sizes.contains(&("web".to_string(), 1280)));
+    }
+
+    #[test]
+    fn prototype_follows_order_and_escapes_screens() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        let screen = |title: &str| {
+            format!(
+                r#"<!DOCTYPE html><html data-title="{}" data-screen-type="mobile"><body>"{}"</body></html>"#,
+                title, title
+            )
+        };
+        fs::write(screens.join("home.html"), screen("Home")).unwrap();
+        fs::write(screens.join("profile.html"), screen("Profile")).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let cwd = session.path().to_string_lossy().to_string();
+        let export = tauri::async_runtime::block_on(export_prototype(
+            cwd.clone(),
+            out.path().join("deck").to_string_lossy().to_string(),
+            vec!["profile.html".to_string(), "home.html".to_string()],
+        ))
+        .unwrap();
+        let written = export.path;
+        assert!(written.ends_with("deck.html"));
+        assert_eq!(export.screen_count, 2);
+
+        let deck = fs::read_to_string(&written).unwrap();
+        assert_eq!(deck.matches("<iframe").count(), 2);
+        assert!(deck.find("&quot;Profile&quot;").unwrap() < deck.find("&quot;Home&quot;").unwrap());
+        assert!(deck.contains(r#"width="393""#));
+
+        let missing = export_prototype(cwd, written, vec!["missing.html".to_string()]);
+        assert!(tauri::async_runtime::block_on(missing).is_err());
+    }
+
+    const THEMED: &str = r#"<!DOCTYPE html><html><head><style type="text/tailwindcss">
+    @theme {
+      --font-sans: "DM Sans", system-ui, sans-serif;
+      --color-background: #fafafa;
+      --color-primary: oklch(0.55 0.2 250);
+      --radius-lg: 0.75rem;
+    }
+  </style></head><body></body></html>"#;
+
+    #[test]
+    fn theme_block_parses_into_tokens() {
+        let tokens = parse_theme_tokens(THEMED);
+        assert_eq!(
+            tokens,
+            vec![
+                ("font-sans".to_string(), r#""DM Sans", system-ui, sans-serif"#.to_string()),
+                ("color-background".to_string(), "#fafafa".to_string()),
+                ("color-primary".to_string(), "oklch(0.55 0.2 250)".to_string()),
+                ("radius-lg".to_string(), "0.75rem".to_string()),
+            ]
+        );
+
+        let css = render_design_tokens(&tokens, "css").unwrap();
+        assert!(css.starts_with(":root {\n  --font-sans:"));
+        assert!(css.contains("  --color-background: #fafafa;\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_design_tokens(&tokens, "json").unwrap()).unwrap();
+        assert_eq!(json["color"]["primary"]["$value"], "oklch(0.55 0.2 250)");
+        assert_eq!(json["color"]["primary"]["$type"], "color");
+        assert_eq!(json["radius"]["lg"]["$type"], "dimension");
+        assert!(render_design_tokens(&tokens, "scss").is_err());
+    }
+
+    #[test]
+    fn theme_modifiers_and_bare_group_values_are_kept() {
+        let html = r#"<style type="text/tailwindcss">
+    @theme inline {
+      --spacing: 0.25rem;
+      --spacing-lg: 2rem;
+      --color-ink: #111;
+    }
+  </style>"#;
+        let tokens = parse_theme_tokens(html);
+        assert_eq!(tokens.len(), 3);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_design_tokens(&tokens, "json").unwrap()).unwrap();
+        assert_eq!(json["spacing"]["DEFAULT"]["$value"], "0.25rem");
+        assert_eq!(json["spacing"]["DEFAULT"]["$type"], "dimension");
+        assert_eq!(json["spacing"]["lg"]["$value"], "2rem");
+        assert_eq!(json["color"]["ink"]["$value"], "#111");
+    }
+
+    #[test]
+    fn session_tokens_report_conflicts() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(screens.join("a.html"), THEMED).unwrap();
+        fs::write(screens.join("b.html"), THEMED.replace("#fafafa", "#ffffff")).unwrap();
+
+        let exported =
+            export_session_design_tokens(session.path().to_string_lossy().to_string(), "css".to_string())
+                .unwrap();
+        assert_eq!(exported.conflicts.len(), 1);
+        assert_eq!(exported.conflicts[0].name, "color-background");
+        assert_eq!(exported.content.matches("--color-background").count(), 1);
+    }
+
+    #[test]
+    fn gallery_embeds_every_screen_scaled_by_type() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(
+            screens.join("home.html"),
+            r#"<!DOCTYPE html><html data-title="Home &amp; Feed" data-screen-type="mobile"><body></body></html>"#,
+        )
+        .unwrap();
+        fs::write(screens.join("dash.html"), COMPLETE).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let export = tauri::async_runtime::block_on(export_gallery_html(
+            session.path().to_string_lossy().to_string(),
+            out.path().join("review").to_string_lossy().to_string(),
+        ))
+        .unwrap();
+        assert_eq!(export.screen_count, 2);
+        assert!(export.path.ends_with("review.html"));
+
+        let page = fs::read_to_string(&export.path).unwrap();
+        assert_eq!(page.matches("<figure>").count(), 2);
+        // 393x852 mobile and 1280x800 web frames at GALLERY_SCALE
+        assert!(page.contains("width: 157px; height: 341px"));
+        assert!(page.contains("width: 512px; height: 320px"));
+        assert!(page.contains("<title>review</title>"));
+    }
+
+    #[test]
+    fn compare_sessions_buckets_screens() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        for dir in [&a, &b] {
+            fs::create_dir_all(dir.path().join("screens")).unwrap();
+            fs::write(dir.path().join("screens/home.html"), COMPLETE).unwrap();
+        }
+        fs::write(a.path().join("screens/profile.html"), COMPLETE).unwrap();
+        fs::write(b.path().join("screens/profile.html"), "<html>changed</html>").unwrap();
+        fs::write(a.path().join("screens/old.html"), COMPLETE).unwrap();
+        fs::write(b.path().join("screens/new.html"), COMPLETE).unwrap();
+
+        let comparison = compare_sessions(
+            a.path().to_string_lossy().to_string(),
+            b.path().to_string_lossy().to_string(),
+        );
+        assert_eq!(comparison.identical, vec!["home.html"]);
+        assert_eq!(comparison.different, vec!["profile.html"]);
+        assert_eq!(comparison.only_in_a, vec!["old.html"]);
+        assert_eq!(comparison.only_in_b, vec!["new.html"]);
+    }
+
+    #[test]
+    fn compare_sessions_covers_root_and_flow_screens() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        // Legacy root design on one side, screens/ on the other: same screen
+        fs::write(a.path().join("home.html"), COMPLETE).unwrap();
+        fs::create_dir_all(b.path().join("screens/onboarding")).unwrap();
+        fs::write(b.path().join("screens/home.html"), COMPLETE).unwrap();
+        // A flow screen doesn't collide with a top-level one of the same name
+        fs::write(b.path().join("screens/onboarding/home.html"), COMPLETE).unwrap();
+
+        let comparison = compare_sessions(
+            a.path().to_string_lossy().to_string(),
+            b.path().to_string_lossy().to_string(),
+        );
+        assert_eq!(comparison.identical, vec!["home.html"]);
+        assert_eq!(comparison.only_in_b, vec!["onboarding/home.html"]);
+    }
+
+    #[test]
+    fn export_appends_html_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("home.html");
+        fs::write(&source, COMPLETE).unwrap();
+
+        let written = export_design_html(
+            source.to_string_lossy().to_string(),
+            dir.path().join("Home Screen").to_string_lossy().to_string(),
+        )
+        .unwrap();
+        assert_eq!(PathBuf::from(&written), dir.path().join("Home Screen.html"));
+        assert_eq!(fs::read_to_string(written).unwrap(), COMPLETE);
+    }
+
+    #[test]
+    fn full_document_is_copied_unchanged() {
+        assert_eq!(wrap_design_fragment(COMPLETE), COMPLETE);
+    }
+
+    #[test]
+    fn fragment_is_wrapped_with_theme_in_head() {
+        let fragment = r#"<style type="text/tailwindcss">@theme { --color-accent: #f00; }</style>
+<body data-title="Home" data-screen-type="mobile" class="bg-background"><h1>Hi</h1></body>"#;
+
+        let doc = wrap_design_fragment(fragment);
+        assert!(doc.starts_with("<!DOCTYPE html>"));
+        assert!(doc.contains(r#"data-title="Home""#));
+        assert!(doc.contains("width=393"));
+        let head_end = doc.find("</head>").unwrap();
+        assert!(doc.find("@theme").unwrap() < head_end);
+        assert!(doc.contains(r#"<body data-title="Home" data-screen-type="mobile" class="bg-background">"#));
+        assert!(is_well_formed_html(&doc));
+    }
 }
@@ -0,0 +1,35 @@
+//! macOS dock tile badge, used to show in-progress generations while the app
+//! is in the background.
+
+use crate::error::AppResult;
+use tauri::AppHandle;
+
+/// Set the dock badge, or clear it with `None`. No-op on other platforms.
+#[tauri::command]
+pub fn set_dock_badge(app: AppHandle, label: Option<String>) -> AppResult<()> {
+    #[cfg(target_os = "macos")]
+    {
+        use tauri::Manager;
+
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+        window
+            .set_badge_label(label.filter(|l| !l.is_empty()))
+            .map_err(|e| format!("Failed to set dock badge: {}", e))?;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app, label);
+
+    Ok(())
+}
+
+/// Clear the badge once the user is looking at the app again
+pub fn clear_dock_badge<R: tauri::Runtime>(window: &tauri::Window<R>) {
+    #[cfg(target_os = "macos")]
+    let _ = window.set_badge_label(None);
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window;
+}
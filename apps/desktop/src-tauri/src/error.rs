@@ -1,3 +1,4 @@
+use serde::ser::SerializeStruct;
 use serde::Serialize;
 use thiserror::Error;
 
@@ -15,17 +16,61 @@ pub enum AppError {
     #[error("Failed to start OpenCode server: {0}")]
     ServerStart(String),
 
+    #[error("OpenCode server is not running")]
+    ServerNotRunning,
+
+    #[error("Session {0} not found")]
+    SessionNotFound(String),
+
+    #[error("Project not ready: {0}")]
+    ProjectNotReady(String),
+
+    #[error("Port {0} is already in use")]
+    PortInUse(u16),
+
+    #[error("Failed to install skill: {0}")]
+    SkillInstallFailed(String),
+
+    #[error("Operation in progress: {0}")]
+    Busy(String),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
     #[error("{0}")]
     Custom(String),
 }
 
-// Implement Serialize for Tauri command error handling
+impl AppError {
+    /// Stable identifier the frontend can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::OpenCodeNotFound => "opencode_not_found",
+            AppError::Io(_) => "io",
+            AppError::Json(_) => "json",
+            AppError::ServerStart(_) => "server_start",
+            AppError::ServerNotRunning => "server_not_running",
+            AppError::SessionNotFound(_) => "session_not_found",
+            AppError::ProjectNotReady(_) => "project_not_ready",
+            AppError::PortInUse(_) => "port_in_use",
+            AppError::SkillInstallFailed(_) => "skill_install_failed",
+            AppError::Busy(_) => "busy",
+            AppError::Timeout(_) => "timeout",
+            AppError::Custom(_) => "custom",
+        }
+    }
+}
+
+// Serialized as `{ "code": "...", "message": "..." }` for Tauri command errors
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
     }
 }
 
@@ -42,3 +87,33 @@ impl From<&str> for AppError {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let err = AppError::SessionNotFound("abc".to_string());
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "session_not_found", "message": "Session abc not found" })
+        );
+
+        let err = AppError::PortInUse(4096);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "port_in_use", "message": "Port 4096 is already in use" })
+        );
+    }
+
+    #[test]
+    fn string_errors_stay_custom() {
+        let err: AppError = "something odd".into();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "custom", "message": "something odd" })
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! Make exported screens work offline by embedding what they load from the
+//! network: scripts and stylesheets inline, fonts and images as `data:` URIs,
+//! and Iconify icons as inline SVG.
+//!
+//! Fetching and rewriting are split. `fetch_assets` downloads every asset the
+//! screens of one export reference, once, and `inline_assets` rewrites each
+//! screen from that cache without touching the network. Anything that
+//! couldn't be fetched stays remote and is still listed by `remote_assets`.
+
+use base64::Engine;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// Iconify renders icons from this API; `fetch_assets` asks it for the SVGs
+const ICONIFY_API: &str = "https://api.iconify.design";
+
+/// Google Fonts serves woff2 only to browsers it recognises
+const FETCH_USER_AGENT: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+/// A downloaded asset and the content type it was served with
+pub(crate) struct Asset {
+    content_type: String,
+    bytes: Vec<u8>,
+}
+
+/// Assets fetched for one export, keyed by URL
+pub(crate) type AssetCache = HashMap<String, Asset>;
+
+fn tag_re() -> Regex {
+    Regex::new(r#"(?is)<(script|link|img)\b([^>]*)>"#).expect("valid regex")
+}
+
+fn icon_re() -> Regex {
+    Regex::new(r#"(?is)<(span|i)\b([^>]*)>\s*</(span|i)>"#).expect("valid regex")
+}
+
+fn import_re() -> Regex {
+    Regex::new(r#"(?i)@import\s+(?:url\()?\s*["']?(https?://[^"')\s;]+)["']?\s*\)?[^;]*;"#)
+        .expect("valid regex")
+}
+
+fn css_url_re() -> Regex {
+    Regex::new(r#"(?i)url\(\s*["']?(https?://[^"')\s]+)["']?\s*\)"#).expect("valid regex")
+}
+
+/// Attributes of a tag as written, in source order. Values are left escaped.
+fn parse_attrs(attrs: &str) -> Vec<(String, String)> {
+    let attr_re = Regex::new(r#"([\w:-]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#)
+        .expect("valid regex");
+    attr_re
+        .captures_iter(attrs)
+        .map(|caps| {
+            let value = caps.get(2).or(caps.get(3)).or(caps.get(4));
+            (
+                caps[1].to_ascii_lowercase(),
+                value.map(|v| v.as_str().to_string()).unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+fn is_remote(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Remote URL a `<script>`, stylesheet `<link>` or `<img>` loads, if any.
+/// Other `<link>`s (preconnect hints, icons) aren't needed to render.
+fn tag_url<'a>(tag: &str, attrs: &'a [(String, String)]) -> Option<&'a str> {
+    let url = match tag.to_ascii_lowercase().as_str() {
+        "script" | "img" => attr(attrs, "src")?,
+        _ => {
+            let rel = attr(attrs, "rel").unwrap_or_default().to_ascii_lowercase();
+            if !rel.split_whitespace().any(|r| r == "stylesheet") {
+                return None;
+            }
+            attr(attrs, "href")?
+        }
+    };
+    is_remote(url).then_some(url)
+}
+
+/// API URL for an Iconify placeholder (`<span class="iconify"
+/// data-icon="prefix:name">`), carrying over its size, flip and rotation
+fn icon_url(attrs: &[(String, String)]) -> Option<String> {
+    let class = attr(attrs, "class")?;
+    if !class.split_whitespace().any(|c| c == "iconify" || c == "iconify-inline") {
+        return None;
+    }
+    let (prefix, name) = attr(attrs, "data-icon")?.split_once(':')?;
+    let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid(prefix) || !valid(name) {
+        return None;
+    }
+
+    let params: Vec<String> = [
+        ("width", "data-width"),
+        ("height", "data-height"),
+        ("flip", "data-flip"),
+        ("rotate", "data-rotate"),
+    ]
+    .iter()
+    .filter_map(|(param, data)| {
+        let value = attr(attrs, data)?;
+        value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == ',')
+            .then(|| format!("{}={}", param, value))
+    })
+    .collect();
+    let mut url = format!("{}/{}/{}.svg", ICONIFY_API, prefix, name);
+    if !params.is_empty() {
+        url.push('?');
+        url.push_str(&params.join("&"));
+    }
+    Some(url)
+}
+
+/// URLs a screen pulls from the network, in document order without repeats:
+/// scripts, stylesheets, `@import`s, CSS `url()`s, images and Iconify icons.
+/// Exports report whatever is left after inlining, since the files they write
+/// don't work offline while any remain.
+pub(crate) fn remote_assets(html: &str) -> Vec<String> {
+    let mut found: Vec<(usize, String)> = Vec::new();
+    for caps in tag_re().captures_iter(html) {
+        if let Some(url) = tag_url(&caps[1], &parse_attrs(&caps[2])) {
+            found.push((caps.get(0).map_or(0, |m| m.start()), url.to_string()));
+        }
+    }
+    for caps in icon_re().captures_iter(html) {
+        if let Some(url) = icon_url(&parse_attrs(&caps[2])) {
+            found.push((caps.get(0).map_or(0, |m| m.start()), url));
+        }
+    }
+    for re in [import_re(), css_url_re()] {
+        for caps in re.captures_iter(html) {
+            found.push((caps.get(1).map_or(0, |m| m.start()), caps[1].to_string()));
+        }
+    }
+    found.sort_by_key(|(pos, _)| *pos);
+
+    let mut urls: Vec<String> = Vec::new();
+    for (_, url) in found {
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// Fetch one asset, or `None` if it can't be downloaded
+async fn fetch_asset(client: reqwest::Client, url: String) -> Option<Asset> {
+    let response = client.get(&url).send().await.ok()?.error_for_status().ok()?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .unwrap_or("application/octet-stream")
+        .trim()
+        .to_string();
+    let bytes = response.bytes().await.ok()?.to_vec();
+    Some(Asset { content_type, bytes })
+}
+
+/// Fetch `urls` concurrently into `cache`, skipping ones already there.
+/// Failures are logged and left out.
+async fn fetch_into(client: &reqwest::Client, cache: &mut AssetCache, urls: Vec<String>) {
+    let handles: Vec<_> = urls
+        .into_iter()
+        .filter(|url| !cache.contains_key(url))
+        .map(|url| {
+            let task = tauri::async_runtime::spawn(fetch_asset(client.clone(), url.clone()));
+            (url, task)
+        })
+        .collect();
+    for (url, task) in handles {
+        match task.await.ok().flatten() {
+            Some(asset) => {
+                cache.insert(url, asset);
+            }
+            None => println!("[export] Failed to fetch {}, leaving it remote", url),
+        }
+    }
+}
+
+/// Download everything `documents` load from the network, plus the fonts
+/// and images their stylesheets reference
+pub(crate) async fn fetch_assets(documents: &[String]) -> AssetCache {
+    let mut cache = AssetCache::new();
+    let Ok(client) = reqwest::Client::builder()
+        .user_agent(FETCH_USER_AGENT)
+        .timeout(std::time::Duration::from_secs(20))
+        .build()
+    else {
+        return cache;
+    };
+
+    let mut urls: Vec<String> = Vec::new();
+    for document in documents {
+        for url in remote_assets(document) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    fetch_into(&client, &mut cache, urls).await;
+
+    let nested: Vec<String> = cache
+        .values()
+        .filter(|asset| asset.content_type == "text/css")
+        .flat_map(|asset| remote_assets(&String::from_utf8_lossy(&asset.bytes)))
+        .collect();
+    fetch_into(&client, &mut cache, nested).await;
+    cache
+}
+
+fn data_uri(asset: &Asset) -> String {
+    format!(
+        "data:{};base64,{}",
+        asset.content_type,
+        base64::engine::general_purpose::STANDARD.encode(&asset.bytes)
+    )
+}
+
+/// Replace the `url()`s in `css` with `data:` URIs for the cached ones
+fn inline_css_urls(css: &str, cache: &AssetCache) -> String {
+    css_url_re()
+        .replace_all(css, |caps: &Captures| match cache.get(&caps[1]) {
+            Some(asset) => format!("url(\"{}\")", data_uri(asset)),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// A cached stylesheet with its `url()`s inlined, safe to put in `<style>`
+fn inline_stylesheet(url: &str, cache: &AssetCache) -> Option<String> {
+    let css = String::from_utf8_lossy(&cache.get(url)?.bytes).into_owned();
+    Some(inline_css_urls(&css, cache).replace("</style", "<\\/style"))
+}
+
+/// The tag's attributes without `skip`, re-serialised
+fn attrs_without(attrs: &[(String, String)], skip: &[&str]) -> String {
+    attrs
+        .iter()
+        .filter(|(name, _)| !skip.contains(&name.as_str()))
+        .map(|(name, value)| format!(" {}=\"{}\"", name, value))
+        .collect()
+}
+
+/// Rewrite `html` so every asset found in `cache` is embedded. Assets that
+/// aren't cached keep their remote URL.
+pub(crate) fn inline_assets(html: &str, cache: &AssetCache) -> String {
+    let html = import_re().replace_all(html, |caps: &Captures| {
+        inline_stylesheet(&caps[1], cache).unwrap_or_else(|| caps[0].to_string())
+    });
+
+    let html = tag_re().replace_all(&html, |caps: &Captures| {
+        let attrs = parse_attrs(&caps[2]);
+        match caps[1].to_ascii_lowercase().as_str() {
+            "link" => {
+                let rel = attr(&attrs, "rel").unwrap_or_default().to_ascii_lowercase();
+                let href = attr(&attrs, "href").unwrap_or_default();
+                // Connection hints are pointless once nothing is fetched
+                if is_remote(href) && (rel == "preconnect" || rel == "dns-prefetch") {
+                    return String::new();
+                }
+                match tag_url(&caps[1], &attrs).and_then(|url| inline_stylesheet(url, cache)) {
+                    Some(css) => format!("<style>{}</style>", css),
+                    None => caps[0].to_string(),
+                }
+            }
+            "img" => match tag_url(&caps[1], &attrs).and_then(|url| cache.get(url)) {
+                Some(asset) => format!(
+                    "<img{} src=\"{}\">",
+                    attrs_without(&attrs, &["src", "srcset"]),
+                    data_uri(asset)
+                ),
+                None => caps[0].to_string(),
+            },
+            _ => caps[0].to_string(),
+        }
+    });
+
+    let html = icon_re().replace_all(&html, |caps: &Captures| {
+        let attrs = parse_attrs(&caps[2]);
+        let svg = icon_url(&attrs)
+            .and_then(|url| cache.get(&url))
+            .map(|asset| String::from_utf8_lossy(&asset.bytes).into_owned())
+            .filter(|svg| svg.trim_start().starts_with("<svg"));
+        match svg {
+            Some(svg) => svg.trim().replacen(
+                "<svg",
+                &format!(
+                    "<svg{} aria-hidden=\"true\" role=\"img\"",
+                    attrs_without(
+                        &attrs,
+                        &["data-icon", "data-width", "data-height", "data-flip", "data-rotate"]
+                    )
+                ),
+                1,
+            ),
+            None => caps[0].to_string(),
+        }
+    });
+
+    let html = inline_css_urls(&html, cache);
+
+    // Scripts go last so the passes above never rewrite inside their code
+    let script_re = Regex::new(r#"(?is)<script\b([^>]*)>\s*</script>"#).expect("valid regex");
+    script_re
+        .replace_all(&html, |caps: &Captures| {
+            let attrs = parse_attrs(&caps[1]);
+            let Some(asset) = attr(&attrs, "src").and_then(|src| cache.get(src)) else {
+                return caps[0].to_string();
+            };
+            let script = String::from_utf8_lossy(&asset.bytes).replace("</script", "<\\/script");
+            format!(
+                "<script{}>{}</script>",
+                attrs_without(&attrs, &["src", "async", "defer", "crossorigin", "integrity"]),
+                script
+            )
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(content_type: &str, body: &str) -> Asset {
+        Asset {
+            content_type: content_type.to_string(),
+            bytes: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn remote_assets_lists_cdn_scripts_and_fonts() {
+        let html = r#"<html><head>
+  <script src="https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4"></script>
+  <link rel="preconnect" href="https://fonts.googleapis.com">
+  <link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+  <style>@import url('https://fonts.googleapis.com/css2?family=Lora');</style>
+  <script src="https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4"></script>
+  <script src="local.js"></script>
+</head><body>
+  <span class="iconify" data-icon="solar:home-bold" data-width="24"></span>
+  <img src="https://images.unsplash.com/photo-1?w=400" alt="">
+</body></html>"#;
+        assert_eq!(
+            remote_assets(html),
+            vec![
+                "https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4",
+                "https://fonts.googleapis.com/css2?family=Inter",
+                "https://fonts.googleapis.com/css2?family=Lora",
+                "https://api.iconify.design/solar/home-bold.svg?width=24",
+                "https://images.unsplash.com/photo-1?w=400",
+            ]
+        );
+        assert!(remote_assets("<div>offline</div>").is_empty());
+    }
+
+    #[test]
+    fn inline_embeds_cached_assets_and_keeps_the_rest_remote() {
+        let html = r#"<html><head>
+  <link rel="preconnect" href="https://fonts.googleapis.com">
+  <link href="https://fonts.googleapis.com/css2?family=DM+Sans" rel="stylesheet">
+  <script src="https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4"></script>
+  <script src="https://code.iconify.design/3/3.1.0/iconify.min.js"></script>
+</head><body>
+  <span class="iconify text-red-500" data-icon="solar:home-bold" data-width="24"></span>
+  <img class="cover" src="https://images.unsplash.com/photo-1?w=400">
+</body></html>"#;
+        let mut cache = AssetCache::new();
+        cache.insert(
+            "https://fonts.googleapis.com/css2?family=DM+Sans".to_string(),
+            asset("text/css", "@font-face { src: url(https://fonts.gstatic.com/dm.woff2); }"),
+        );
+        cache.insert("https://fonts.gstatic.com/dm.woff2".to_string(), asset("font/woff2", "woff"));
+        cache.insert(
+            "https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4".to_string(),
+            asset("text/javascript", "tw('</script>')"),
+        );
+        cache.insert(
+            "https://api.iconify.design/solar/home-bold.svg?width=24".to_string(),
+            asset("image/svg+xml", r#"<svg xmlns="http://www.w3.org/2000/svg" width="24"></svg>"#),
+        );
+        cache.insert("https://images.unsplash.com/photo-1?w=400".to_string(), asset("image/jpeg", "jpg"));
+
+        let inlined = inline_assets(html, &cache);
+        assert!(!inlined.contains("preconnect"));
+        assert!(inlined.contains("<style>@font-face { src: url(\"data:font/woff2;base64,d29mZg==\"); }</style>"));
+        assert!(inlined.contains("<script>tw('<\\/script>')</script>"));
+        assert!(inlined.contains(r#"<svg class="iconify text-red-500" aria-hidden="true" role="img" xmlns="#));
+        assert!(inlined.contains(r#"<img class="cover" src="data:image/jpeg;base64,anBn">"#));
+        // Not cached, so still loaded from the CDN and still reported
+        assert_eq!(
+            remote_assets(&inlined),
+            vec!["https://code.iconify.design/3/3.1.0/iconify.min.js"]
+        );
+    }
+
+    #[test]
+    fn inline_replaces_css_imports() {
+        let html = "<style>@import url('https://fonts.googleapis.com/css2?family=Lora'); body { color: red; }</style>";
+        let mut cache = AssetCache::new();
+        cache.insert(
+            "https://fonts.googleapis.com/css2?family=Lora".to_string(),
+            asset("text/css", "@font-face { font-family: Lora; }"),
+        );
+        assert_eq!(
+            inline_assets(html, &cache),
+            "<style>@font-face { font-family: Lora; } body { color: red; }</style>"
+        );
+    }
+}
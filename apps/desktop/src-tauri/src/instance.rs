@@ -0,0 +1,103 @@
+//! Single-instance guard for the data directory.
+//!
+//! Two running copies of Dilag would each spawn an OpenCode server and write
+//! `sessions.json`. On startup we write our PID to `instance.lock`; a later
+//! instance that finds a live PID there leaves servers alone. Locks left by a
+//! crashed instance are taken over.
+
+use crate::opencode::is_process_alive;
+use crate::paths::get_instance_lock_file;
+use crate::state::AppState;
+use std::fs;
+use std::path::Path;
+
+fn read_lock_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Take the lock at `path` for `pid`. Returns the holder's PID when another
+/// live process already has it.
+fn acquire_lock_at(path: &Path, pid: u32) -> Option<u32> {
+    if let Some(holder) = read_lock_pid(path) {
+        if holder != pid && is_process_alive(holder) {
+            return Some(holder);
+        }
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(e) = fs::write(path, pid.to_string()) {
+        println!("[instance] Failed to write {}: {}", path.display(), e);
+    }
+    None
+}
+
+/// Remove the lock at `path`, but only if `pid` still owns it
+fn release_lock_at(path: &Path, pid: u32) {
+    if read_lock_pid(path) == Some(pid) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Take the instance lock, recording any other live instance in `state`.
+/// Returns the other instance's PID.
+pub fn acquire_instance_lock(state: &AppState) -> Option<u32> {
+    let holder = acquire_lock_at(&get_instance_lock_file(), std::process::id());
+    *state.other_instance_pid.lock().unwrap() = holder;
+    holder
+}
+
+/// Whether this instance owns the data dir. A second instance must not write
+/// to it (sessions store, settings, window geometry).
+pub fn owns_data_dir(state: &AppState) -> bool {
+    state.other_instance_pid.lock().unwrap().is_none()
+}
+
+/// Release the instance lock on exit, unless another instance owns it
+pub fn release_instance_lock(state: &AppState) {
+    if owns_data_dir(state) {
+        release_lock_at(&get_instance_lock_file(), std::process::id());
+    }
+}
+
+/// PID of another running Dilag that owns the data directory, if any
+#[tauri::command]
+pub fn get_other_instance_pid(state: tauri::State<'_, AppState>) -> Option<u32> {
+    *state.other_instance_pid.lock().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_lock_blocks_and_stale_lock_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join("instance.lock");
+        let own = std::process::id();
+
+        assert_eq!(acquire_lock_at(&lock, own), None);
+        assert_eq!(read_lock_pid(&lock), Some(own));
+
+        // A different PID sees us as the live holder
+        assert_eq!(acquire_lock_at(&lock, own + 1), Some(own));
+
+        // A lock left by an exited process is stale
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        fs::write(&lock, dead.to_string()).unwrap();
+        assert_eq!(acquire_lock_at(&lock, own), None);
+        assert_eq!(read_lock_pid(&lock), Some(own));
+
+        // Only the owner removes the lock
+        release_lock_at(&lock, own + 1);
+        assert!(lock.exists());
+        release_lock_at(&lock, own);
+        assert!(!lock.exists());
+    }
+}
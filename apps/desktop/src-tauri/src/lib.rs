@@ -3,14 +3,24 @@
 mod app_info;
 mod capture;
 mod designs;
+mod dock;
 mod error;
+mod inline_assets;
+mod instance;
 mod menu;
 mod opencode;
 mod paths;
+mod project_files;
 mod screen_validator;
+mod search;
 mod sessions;
+mod settings;
 mod state;
 mod theme;
+mod thumbnails;
+mod updater;
+mod watcher;
+mod window_state;
 mod zoom;
 
 use tauri::webview::WebviewWindowBuilder;
@@ -18,6 +28,9 @@ use tauri::{Emitter, Manager, TitleBarStyle};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let mut context = tauri::generate_context!();
+    updater::apply_update_channel(&mut context);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
@@ -30,26 +43,48 @@ pub fn run() {
             let menu = menu::setup_menu(app.handle())?;
             app.set_menu(menu)?;
 
-            let port = opencode::get_free_port();
+            let other_instance_pid =
+                instance::acquire_instance_lock(&app.state::<state::AppState>());
+            if let Some(pid) = other_instance_pid {
+                // Recorded in AppState; the frontend reads it with `get_other_instance_pid`
+                // once mounted, since nothing is listening for events this early
+                println!("[setup] Another Dilag instance (pid {}) owns the data dir", pid);
+            } else {
+                // Only the owning instance touches sessions.json
+                sessions::migrate_legacy_layout();
+            }
+
+            let port = opencode::resolve_startup_port();
             {
                 let app_state = app.state::<state::AppState>();
                 *app_state.opencode_port.lock().unwrap() = Some(port);
             }
             println!("[setup] OpenCode port: {}", port);
+            println!("[setup] Subprocess PATH: {}", opencode::build_augmented_path());
+            if other_instance_pid.is_none() {
+                opencode::spawn_idle_monitor(app.handle().clone());
+            }
+
+            let geometry = window_state::restore_geometry(app.handle());
 
             let win_builder =
                 WebviewWindowBuilder::new(app, "main", tauri::WebviewUrl::App("index.html".into()))
                     .title("Dilag")
-                    .inner_size(1000.0, 700.0)
                     .min_inner_size(768.0, 600.0)
-                    .maximized(true)
                     .title_bar_style(TitleBarStyle::Overlay)
                     .hidden_title(true)
                     .traffic_light_position(tauri::LogicalPosition::new(16.0, 18.0))
-                    .initialization_script(&format!(
+                    .initialization_script(format!(
                         r#"window.__DILAG__ = {{ port: {} }};"#,
                         port
                     ));
+            let win_builder = match geometry {
+                Some(g) => win_builder
+                    .inner_size(g.width, g.height)
+                    .position(g.x, g.y)
+                    .maximized(g.maximized),
+                None => win_builder.inner_size(1000.0, 700.0).maximized(true),
+            };
 
             let window = win_builder.build()?;
 
@@ -73,6 +108,24 @@ pub fn run() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if window.label() != "main" {
+                return;
+            }
+            match event {
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                    window_state::track_window_geometry(window);
+                }
+                // A second instance leaves the shared settings to the owner
+                tauri::WindowEvent::CloseRequested { .. }
+                    if instance::owns_data_dir(&window.state::<state::AppState>()) =>
+                {
+                    window_state::save_window_geometry();
+                }
+                tauri::WindowEvent::Focused(true) => dock::clear_dock_badge(window),
+                _ => {}
+            }
+        })
         .on_menu_event(|app, event| {
             let event_id = event.id().as_ref();
 
@@ -120,34 +173,133 @@ pub fn run() {
             // OpenCode commands
             opencode::check_opencode_installation,
             opencode::check_bun_installation,
+            opencode::get_effective_path,
             opencode::install_dependencies,
+            opencode::cancel_install_dependencies,
             opencode::get_opencode_port,
+            opencode::set_opencode_port,
+            opencode::get_opencode_uptime_secs,
+            opencode::list_opencode_models,
+            opencode::clear_models_cache,
             opencode::start_opencode_server,
             opencode::stop_opencode_server,
             opencode::restart_opencode_server,
             opencode::is_opencode_running,
+            opencode::mark_opencode_activity,
+            opencode::ping_opencode,
+            opencode::free_port,
+            opencode::stop_all_servers,
+            opencode::read_opencode_log,
+            opencode::get_opencode_config,
+            // Auth commands
+            opencode::check_opencode_auth,
+            opencode::open_opencode_login,
+            // Permission commands
+            opencode::get_bash_permissions,
+            opencode::set_bash_permissions,
+            opencode::reset_bash_permissions,
+            // OpenCode option commands
+            opencode::get_opencode_options,
+            opencode::set_opencode_option,
+            opencode::get_opencode_data_isolation,
+            opencode::set_opencode_data_isolation,
+            opencode::get_opencode_plugins,
+            opencode::set_opencode_plugins,
+            opencode::validate_opencode_plugins,
+            opencode::get_bind_lan,
+            opencode::set_bind_lan,
+            opencode::get_lan_address,
+            opencode::get_opencode_idle_timeout,
+            opencode::set_opencode_idle_timeout,
             // Skills commands
             opencode::list_installed_skills,
             opencode::preview_skills,
             opencode::install_skill,
             opencode::remove_skill,
+            opencode::repair_skills,
+            opencode::get_skill_content,
             // Session commands
             sessions::create_session_dir,
+            sessions::import_web_project,
             sessions::get_session_cwd,
+            sessions::get_session_paths,
+            sessions::get_session_mode,
+            sessions::get_session_cache_sizes,
+            sessions::clear_session_caches,
+            sessions::get_session,
             sessions::save_session_metadata,
+            sessions::set_opencode_session_id,
+            sessions::set_session_platform,
             sessions::load_sessions_metadata,
+            sessions::load_sessions_metadata_sorted,
+            sessions::query_sessions,
             sessions::delete_session_metadata,
             sessions::toggle_session_favorite,
+            sessions::set_last_active_session,
+            sessions::get_last_active_session,
+            sessions::inspect_session_zip,
             // Design commands
             designs::load_session_designs,
             designs::copy_session_designs,
+            designs::copy_design,
+            designs::duplicate_design,
+            designs::count_session_designs,
+            designs::recent_designs,
+            designs::design_hash,
+            designs::compare_sessions,
+            designs::verify_session,
+            designs::get_design_for_clipboard,
+            designs::export_design_html,
+            designs::export_figma_bundle,
+            designs::export_prototype,
+            designs::export_gallery_html,
+            designs::export_design_tokens,
+            designs::export_session_design_tokens,
             designs::delete_design,
             screen_validator::validate_screen_html,
+            screen_validator::lint_design,
+            // Search commands
+            search::search_designs,
+            search::rebuild_search_index,
+            // Thumbnail commands
+            thumbnails::get_thumbnail,
+            thumbnails::save_thumbnail,
+            thumbnails::list_stale_thumbnails,
+            // Watcher commands
+            watcher::start_session_watch,
+            watcher::stop_session_watch,
+            watcher::await_design_write,
+            watcher::pause_session_watch,
+            watcher::resume_session_watch,
+            // Project file commands
+            project_files::list_project_files,
+            project_files::read_project_file,
+            project_files::read_project_file_bytes,
+            project_files::rename_project_path,
+            project_files::autosave_project_file,
             // Capture commands
             capture::capture_html_to_image,
+            capture::resolve_design_capture,
+            capture::capture_supported,
+            capture::frame_screenshot,
             // App info commands
             app_info::get_app_info,
+            app_info::get_app_status,
+            app_info::check_free_space,
+            app_info::set_data_dir,
+            app_info::run_diagnostics,
             app_info::reset_all_data,
+            app_info::set_busy,
+            app_info::clear_busy,
+            // Instance commands
+            instance::get_other_instance_pid,
+            // Updater commands
+            updater::get_update_channel,
+            updater::set_update_channel,
+            updater::check_for_update,
+            updater::download_and_install_update,
+            // Dock commands
+            dock::set_dock_badge,
             // Theme commands
             theme::set_titlebar_theme,
             // Zoom commands
@@ -157,7 +309,18 @@ pub fn run() {
             zoom::zoom_out,
             zoom::zoom_reset,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // `Exit` fires for Cmd-Q / app quit as well as closing the last window
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<state::AppState>();
+                opencode::kill_tracked_server(&state);
+                if instance::owns_data_dir(&state) {
+                    window_state::save_window_geometry();
+                }
+                instance::release_instance_lock(&state);
+            }
+        });
 }
 
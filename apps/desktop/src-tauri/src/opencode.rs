@@ -1,12 +1,15 @@
 use crate::error::{AppError, AppResult};
-use crate::paths::{get_dilag_dir, get_opencode_config_dir, get_sessions_dir};
+use crate::paths::{get_dilag_dir, get_opencode_config_dir, get_opencode_data_home, get_sessions_dir};
+use crate::settings::{load_settings, save_settings};
 use crate::state::AppState;
 use serde::Serialize;
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::net::TcpListener;
-use std::path::PathBuf;
-use tauri::AppHandle;
+use std::net::{Ipv4Addr, TcpListener, UdpSocket};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
 
 /// Mobile design skill content - embedded from assets
@@ -75,6 +78,65 @@ pub fn get_free_port() -> u16 {
         .port()
 }
 
+/// Check whether a port can currently be bound on localhost
+pub(crate) fn is_port_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Primary non-loopback IPv4 address. Connecting a UDP socket sends nothing;
+/// it just makes the OS pick the interface it would route through.
+fn primary_lan_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 80)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        std::net::IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+/// Shown whenever LAN binding is on
+const LAN_BINDING_WARNING: &str =
+    "The OpenCode server is reachable by anyone on your network and has no authentication. Only enable this on networks you trust.";
+
+/// Remember the port so the next launch can try to reuse it
+fn persist_port(port: u16) {
+    let mut settings = load_settings();
+    if settings.opencode_port == Some(port) {
+        return;
+    }
+    settings.opencode_port = Some(port);
+    if let Err(e) = save_settings(&settings) {
+        println!("[opencode] Failed to persist port {}: {}", port, e);
+    }
+}
+
+/// First of the preferred ports (in order) that `is_free` accepts
+fn first_free_port(preferred: &[Option<u16>], is_free: impl Fn(u16) -> bool) -> Option<u16> {
+    preferred.iter().flatten().copied().find(|&port| is_free(port))
+}
+
+/// Reuse one of the preferred ports if it's free, otherwise take a fresh one
+/// and persist it so the next launch tries it first
+fn resolve_port(preferred: &[Option<u16>], log_prefix: &str) -> u16 {
+    if let Some(port) = first_free_port(preferred, is_port_free) {
+        println!("[{}] Reusing OpenCode port {}", log_prefix, port);
+        return port;
+    }
+    if preferred.iter().any(Option::is_some) {
+        println!("[{}] Previous OpenCode port is in use, replacing it", log_prefix);
+    }
+
+    let port = get_free_port();
+    persist_port(port);
+    port
+}
+
+/// Pick the port for this launch: the persisted one if it's still free,
+/// otherwise a fresh one (which is then persisted).
+pub fn resolve_startup_port() -> u16 {
+    resolve_port(&[load_settings().opencode_port], "setup")
+}
+
 #[derive(Debug, Serialize)]
 pub struct OpenCodeCheckResult {
     pub installed: bool,
@@ -137,7 +199,7 @@ fn get_bun_binary_path() -> Option<PathBuf> {
     candidates.into_iter().find(|path| path.exists() && path.is_file())
 }
 
-fn build_augmented_path() -> String {
+pub(crate) fn build_augmented_path() -> String {
     let existing = std::env::var("PATH").unwrap_or_default();
     let separator = if cfg!(windows) { ";" } else { ":" };
 
@@ -193,6 +255,293 @@ fn build_augmented_path() -> String {
     }
 }
 
+/// Built-in bash permission rules for the design agent. Anything not matched
+/// falls through to `"*": "ask"`. User overrides from settings are layered on top.
+const DEFAULT_BASH_PERMISSIONS: &[(&str, &str)] = &[
+    ("*", "ask"),
+    // Read-only filesystem inspection
+    ("ls", "allow"),
+    ("ls *", "allow"),
+    ("mkdir *", "allow"),
+    ("pwd", "allow"),
+    ("which *", "allow"),
+    ("echo *", "allow"),
+    ("cat *", "allow"),
+    ("head *", "allow"),
+    ("tail *", "allow"),
+    ("wc *", "allow"),
+    ("find", "allow"),
+    ("find *", "allow"),
+    ("grep *", "allow"),
+    ("file *", "allow"),
+    ("stat *", "allow"),
+    ("tree *", "allow"),
+    ("du *", "allow"),
+    ("df *", "allow"),
+    // Read-only git
+    ("git status", "allow"),
+    ("git status *", "allow"),
+    ("git log", "allow"),
+    ("git log *", "allow"),
+    ("git diff", "allow"),
+    ("git diff *", "allow"),
+    ("git branch", "allow"),
+    ("git branch *", "allow"),
+    ("git show *", "allow"),
+    ("git remote *", "allow"),
+    ("git stash list", "allow"),
+    ("git rev-parse *", "allow"),
+    ("git config --get *", "allow"),
+    // Package managers
+    ("bun i", "allow"),
+    ("bun install", "allow"),
+    ("bun install *", "allow"),
+    ("bun add *", "allow"),
+    ("bun remove *", "allow"),
+    ("bun run *", "allow"),
+    ("bun pm ls", "allow"),
+    ("bun pm ls *", "allow"),
+    ("bun x *", "allow"),
+    ("bunx *", "allow"),
+    ("npm i", "allow"),
+    ("npm install", "allow"),
+    ("npm install *", "allow"),
+    ("npm ci", "allow"),
+    ("npm run *", "allow"),
+    ("npm ls", "allow"),
+    ("npm ls *", "allow"),
+    ("npm list", "allow"),
+    ("npm list *", "allow"),
+    ("npx *", "allow"),
+    // Type checking, tests, lint
+    ("tsc", "allow"),
+    ("tsc *", "allow"),
+    ("vitest *", "allow"),
+    ("jest *", "allow"),
+    ("eslint *", "allow"),
+    ("prettier *", "allow"),
+];
+
+const PERMISSION_ACTIONS: &[&str] = &["allow", "ask", "deny"];
+
+/// Merge user overrides on top of the defaults, keeping default ordering and
+/// appending any new patterns after them.
+fn effective_bash_permissions(
+    overrides: &HashMap<String, String>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut map = serde_json::Map::new();
+    for (pattern, action) in DEFAULT_BASH_PERMISSIONS {
+        map.insert(pattern.to_string(), serde_json::Value::from(*action));
+    }
+    let mut extra: Vec<_> = overrides.iter().collect();
+    extra.sort();
+    for (pattern, action) in extra {
+        map.insert(pattern.clone(), serde_json::Value::from(action.as_str()));
+    }
+    map
+}
+
+/// Top-level `opencode.json` options users may override
+const OPENCODE_OPTION_KEYS: &[&str] = &["autoupdate", "share"];
+
+/// `share` values accepted by OpenCode
+const SHARE_MODES: &[&str] = &["manual", "auto", "disabled"];
+
+/// Top-level options with user overrides applied. Defaults keep sharing off
+/// and leave updates to Dilag.
+fn effective_opencode_options(
+    overrides: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut map = serde_json::Map::new();
+    map.insert("autoupdate".to_string(), serde_json::Value::Bool(false));
+    map.insert("share".to_string(), serde_json::Value::from("disabled"));
+    for (key, value) in overrides {
+        if OPENCODE_OPTION_KEYS.contains(&key.as_str()) && !value.is_null() {
+            map.insert(key.clone(), value.clone());
+        }
+    }
+    map
+}
+
+/// Check the key is supported and the value has the right shape. `null` is
+/// accepted for any supported key and means "use the default".
+fn validate_opencode_option(key: &str, value: &serde_json::Value) -> AppResult<()> {
+    if !OPENCODE_OPTION_KEYS.contains(&key) {
+        return Err(AppError::Custom(format!("Unsupported OpenCode option: {}", key)));
+    }
+    let valid = value.is_null()
+        || match key {
+            "share" => value.as_str().is_some_and(|s| SHARE_MODES.contains(&s)),
+            _ => value.is_boolean() || value.as_str() == Some("notify"),
+        };
+    if !valid {
+        let expected = match key {
+            "share" => "manual, auto, or disabled",
+            _ => "true, false, or \"notify\"",
+        };
+        return Err(AppError::Custom(format!(
+            "Invalid value {} for \"{}\" (expected {})",
+            value, key, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Plugins in `opencode.json` unless the user sets their own list
+const DEFAULT_OPENCODE_PLUGINS: &[&str] = &["opencode-antigravity-auth@1.2.8"];
+
+/// How long to wait on the npm registry per plugin
+const PLUGIN_REGISTRY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn effective_opencode_plugins(plugins: &Option<Vec<String>>) -> Vec<String> {
+    match plugins {
+        Some(plugins) => plugins.clone(),
+        None => DEFAULT_OPENCODE_PLUGINS.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+/// Split a pinned `name@version` (or `@scope/name@version`) plugin spec.
+/// `None` when the name isn't a valid npm package name or the version is missing.
+fn parse_plugin_spec(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.rfind('@').filter(|&i| i > 0)?;
+    let (name, version) = (&spec[..at], &spec[at + 1..]);
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with(['.', '_'])
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-._~".contains(c))
+    };
+    let valid_name = match name.strip_prefix('@') {
+        Some(scoped) => scoped
+            .split_once('/')
+            .is_some_and(|(scope, pkg)| valid_part(scope) && valid_part(pkg)),
+        None => valid_part(name),
+    };
+    let valid_version = !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-.+^~<>=*".contains(c));
+    (valid_name && valid_version).then_some((name, version))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginState {
+    /// Already installed in OpenCode's plugin cache at this version
+    Cached,
+    /// Not cached, but the registry has this version
+    Available,
+    /// The registry has no such package or version
+    NotFound,
+    /// Not a `name@version` spec
+    InvalidSpec,
+    /// Couldn't reach the registry to check
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginStatus {
+    pub spec: String,
+    pub state: PluginState,
+    pub message: String,
+}
+
+/// Version of `name` in OpenCode's plugin cache, if installed
+fn cached_plugin_version(name: &str) -> Option<String> {
+    let manifest = dirs::cache_dir()?
+        .join("opencode")
+        .join("node_modules")
+        .join(name)
+        .join("package.json");
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    manifest["version"].as_str().map(String::from)
+}
+
+async fn check_plugin(client: &reqwest::Client, spec: &str) -> PluginStatus {
+    let status = |state, message: String| PluginStatus {
+        spec: spec.to_string(),
+        state,
+        message,
+    };
+    let Some((name, version)) = parse_plugin_spec(spec) else {
+        return status(
+            PluginState::InvalidSpec,
+            "Expected a pinned package like name@1.2.3".to_string(),
+        );
+    };
+    if cached_plugin_version(name).as_deref() == Some(version) {
+        return status(PluginState::Cached, format!("{} {} is installed", name, version));
+    }
+
+    let url = format!("https://registry.npmjs.org/{}/{}", name.replace('/', "%2F"), version);
+    match client.get(&url).timeout(PLUGIN_REGISTRY_TIMEOUT).send().await {
+        Ok(response) if response.status().is_success() => status(
+            PluginState::Available,
+            format!("{} {} will be installed on start", name, version),
+        ),
+        Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => status(
+            PluginState::NotFound,
+            format!("{} {} doesn't exist on npm; the server may fail to start", name, version),
+        ),
+        Ok(response) => status(
+            PluginState::Unreachable,
+            format!("npm registry returned {}", response.status()),
+        ),
+        Err(e) => status(PluginState::Unreachable, format!("Couldn't reach npm registry: {}", e)),
+    }
+}
+
+/// Check each configured OpenCode plugin resolves (cached, or published on
+/// npm) so a bad pin is caught before it stops the server from starting
+#[tauri::command]
+pub async fn validate_opencode_plugins() -> Vec<PluginStatus> {
+    let client = reqwest::Client::new();
+    let mut statuses = Vec::new();
+    for spec in effective_opencode_plugins(&load_settings().opencode_plugins) {
+        statuses.push(check_plugin(&client, &spec).await);
+    }
+    statuses
+}
+
+#[tauri::command]
+pub fn get_opencode_plugins() -> Vec<String> {
+    effective_opencode_plugins(&load_settings().opencode_plugins)
+}
+
+/// Replace the plugin list written to `opencode.json`; `None` restores the
+/// default. Specs must be pinned as `name@version`. Takes effect on next start.
+#[tauri::command]
+pub fn set_opencode_plugins(plugins: Option<Vec<String>>) -> AppResult<Vec<String>> {
+    if let Some(invalid) = plugins
+        .iter()
+        .flatten()
+        .find(|spec| parse_plugin_spec(spec).is_none())
+    {
+        return Err(AppError::Custom(format!(
+            "Invalid plugin \"{}\" (expected name@version)",
+            invalid
+        )));
+    }
+
+    let mut settings = load_settings();
+    settings.opencode_plugins = plugins;
+    save_settings(&settings)?;
+    Ok(effective_opencode_plugins(&settings.opencode_plugins))
+}
+
+/// Write `contents` only when the file doesn't already hold exactly that,
+/// so unchanged config isn't rewritten on every server start.
+/// Returns whether a write happened.
+fn write_if_changed(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<bool> {
+    let (path, contents) = (path.as_ref(), contents.as_ref());
+    if fs::read(path).is_ok_and(|existing| existing == contents) {
+        return Ok(false);
+    }
+    fs::write(path, contents)?;
+    Ok(true)
+}
+
 fn ensure_config_exists() -> AppResult<()> {
     let config_dir = get_opencode_config_dir();
     fs::create_dir_all(&config_dir)?;
@@ -201,99 +550,33 @@ fn ensure_config_exists() -> AppResult<()> {
     let mobile_skill_dir = config_dir.join("skill").join("mobile-design");
     let mobile_examples_dir = mobile_skill_dir.join("examples");
     fs::create_dir_all(&mobile_examples_dir)?;
-    fs::write(mobile_skill_dir.join("SKILL.md"), render_skill(MOBILE_DESIGN_SKILL))?;
-    fs::write(mobile_examples_dir.join("wellness.html"), MOBILE_EXAMPLE_WELLNESS)?;
-    fs::write(mobile_examples_dir.join("finance.html"), MOBILE_EXAMPLE_FINANCE)?;
+    write_if_changed(mobile_skill_dir.join("SKILL.md"), render_skill(MOBILE_DESIGN_SKILL))?;
+    write_if_changed(mobile_examples_dir.join("wellness.html"), MOBILE_EXAMPLE_WELLNESS)?;
+    write_if_changed(mobile_examples_dir.join("finance.html"), MOBILE_EXAMPLE_FINANCE)?;
 
     // Create web-design skill directory, prompt, and exemplars
     let web_skill_dir = config_dir.join("skill").join("web-design");
     let web_examples_dir = web_skill_dir.join("examples");
     fs::create_dir_all(&web_examples_dir)?;
-    fs::write(web_skill_dir.join("SKILL.md"), render_skill(WEB_DESIGN_SKILL))?;
-    fs::write(web_examples_dir.join("editorial.html"), WEB_EXAMPLE_EDITORIAL)?;
-    fs::write(web_examples_dir.join("saas-dashboard.html"), WEB_EXAMPLE_SAAS)?;
+    write_if_changed(web_skill_dir.join("SKILL.md"), render_skill(WEB_DESIGN_SKILL))?;
+    write_if_changed(web_examples_dir.join("editorial.html"), WEB_EXAMPLE_EDITORIAL)?;
+    write_if_changed(web_examples_dir.join("saas-dashboard.html"), WEB_EXAMPLE_SAAS)?;
 
     // Create opencode config
     let config_file = config_dir.join("opencode.json");
-    let config = serde_json::json!({
+    let settings = load_settings();
+    let bash_permissions = effective_bash_permissions(&settings.bash_permissions);
+    let mut config = serde_json::json!({
         "$schema": "https://opencode.ai/config.json",
-        "autoupdate": false,
-        "share": "disabled",
         "default_agent": "build",
-        "plugin": [
-            "opencode-antigravity-auth@1.2.8"
-        ],
+        "plugin": effective_opencode_plugins(&settings.opencode_plugins),
         "agent": {
             "build": {
                 "prompt": BUILD_AGENT_PROMPT
             }
         },
         "permission": {
-            "bash": {
-                "*": "ask",
-
-                "ls": "allow",
-                "ls *": "allow",
-                "mkdir *": "allow",
-                "pwd": "allow",
-                "which *": "allow",
-                "echo *": "allow",
-                "cat *": "allow",
-                "head *": "allow",
-                "tail *": "allow",
-                "wc *": "allow",
-                "find": "allow",
-                "find *": "allow",
-                "grep *": "allow",
-                "file *": "allow",
-                "stat *": "allow",
-                "tree *": "allow",
-                "du *": "allow",
-                "df *": "allow",
-
-                "git status": "allow",
-                "git status *": "allow",
-                "git log": "allow",
-                "git log *": "allow",
-                "git diff": "allow",
-                "git diff *": "allow",
-                "git branch": "allow",
-                "git branch *": "allow",
-                "git show *": "allow",
-                "git remote *": "allow",
-                "git stash list": "allow",
-                "git rev-parse *": "allow",
-                "git config --get *": "allow",
-
-                "bun i": "allow",
-                "bun install": "allow",
-                "bun install *": "allow",
-                "bun add *": "allow",
-                "bun remove *": "allow",
-                "bun run *": "allow",
-                "bun pm ls": "allow",
-                "bun pm ls *": "allow",
-                "bun x *": "allow",
-                "bunx *": "allow",
-
-                "npm i": "allow",
-                "npm install": "allow",
-                "npm install *": "allow",
-                "npm ci": "allow",
-                "npm run *": "allow",
-                "npm ls": "allow",
-                "npm ls *": "allow",
-                "npm list": "allow",
-                "npm list *": "allow",
-                "npx *": "allow",
-
-                "tsc": "allow",
-                "tsc *": "allow",
-                "vitest *": "allow",
-                "jest *": "allow",
-                "eslint *": "allow",
-                "prettier *": "allow"
-            },
+            "bash": bash_permissions,
             "task": "deny",
             "skill": {
                 "mobile-design": "allow",
@@ -302,12 +585,37 @@ fn ensure_config_exists() -> AppResult<()> {
         }
     });
 
+    if let Some(config) = config.as_object_mut() {
+        config.extend(effective_opencode_options(&settings.opencode_options));
+    }
+
     let config_str = serde_json::to_string_pretty(&config)?;
-    fs::write(&config_file, config_str)?;
+    write_if_changed(&config_file, &config_str)?;
 
     Ok(())
 }
 
+/// Upper bound on how long a restart waits for the old server to exit
+const PROCESS_EXIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+const PROCESS_EXIT_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Check whether a process with the given PID is still running
+pub(crate) fn is_process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        // Signal 0 performs the existence/permission check without signalling
+        unsafe { libc::kill(pid as i32, 0) == 0 }
+    }
+    #[cfg(windows)]
+    {
+        std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+            .unwrap_or(false)
+    }
+}
+
 fn kill_process(pid: u32) {
     #[cfg(unix)]
     {
@@ -323,10 +631,123 @@ fn kill_process(pid: u32) {
     }
 }
 
+/// Kill `pid` and everything it spawned, e.g. an install script together with
+/// the curl/tar/unzip it's running. On Unix each process is stopped as it's
+/// found so nothing can fork past the walk, then all are terminated.
+fn kill_process_tree(pid: u32) {
+    #[cfg(unix)]
+    {
+        let mut tree = vec![pid];
+        let mut next = 0;
+        while next < tree.len() {
+            let parent = tree[next];
+            unsafe {
+                libc::kill(parent as i32, libc::SIGSTOP);
+            }
+            let children = std::process::Command::new("pgrep")
+                .args(["-P", &parent.to_string()])
+                .output()
+                .map(|o| parse_lsof_pids(&String::from_utf8_lossy(&o.stdout)))
+                .unwrap_or_default();
+            for child in children {
+                if !tree.contains(&child) {
+                    tree.push(child);
+                }
+            }
+            next += 1;
+        }
+        for &member in &tree {
+            unsafe {
+                libc::kill(member as i32, libc::SIGTERM);
+                libc::kill(member as i32, libc::SIGCONT);
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+    }
+}
+
+/// Kill the tracked OpenCode server, if any. Called from the app exit handler so
+/// `opencode serve` doesn't outlive Dilag and keep holding its port.
+pub fn kill_tracked_server(state: &AppState) {
+    state.opencode_started_at.lock().unwrap().take();
+    if let Some(pid) = state.opencode_pid.lock().unwrap().take() {
+        println!("[shutdown] Killing OpenCode server {}", pid);
+        kill_process(pid);
+    }
+}
+
+/// PIDs from `lsof -ti tcp:<port>` or `pgrep` output (one per line)
+#[cfg(any(unix, test))]
+fn parse_lsof_pids(output: &str) -> Vec<u32> {
+    let mut pids: Vec<u32> = output.lines().filter_map(|l| l.trim().parse().ok()).collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// PIDs listening on `port` from `netstat -ano` output
+#[cfg(any(windows, test))]
+fn parse_netstat_pids(output: &str, port: u16) -> Vec<u32> {
+    let suffix = format!(":{}", port);
+    let mut pids: Vec<u32> = output
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            match cols.as_slice() {
+                [proto, local, _, state, pid]
+                    if proto.eq_ignore_ascii_case("tcp")
+                        && local.ends_with(&suffix)
+                        && state.eq_ignore_ascii_case("listening") =>
+                {
+                    pid.parse().ok()
+                }
+                _ => None,
+            }
+        })
+        .filter(|pid| *pid != 0)
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// Processes listening on `port`. Clients connected to it (the webview, a
+/// browser tab, curl) are left out so they never get killed.
+fn pids_on_port(port: u16) -> Vec<u32> {
+    #[cfg(unix)]
+    {
+        std::process::Command::new("lsof")
+            .args(["-ti", &format!("tcp:{}", port), "-sTCP:LISTEN"])
+            .output()
+            .map(|o| parse_lsof_pids(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or_default()
+    }
+    #[cfg(windows)]
+    {
+        std::process::Command::new("netstat")
+            .args(["-ano", "-p", "tcp"])
+            .output()
+            .map(|o| parse_netstat_pids(&String::from_utf8_lossy(&o.stdout), port))
+            .unwrap_or_default()
+    }
+}
+
 // =============================================================================
 // Tauri Commands
 // =============================================================================
 
+/// The PATH handed to spawned commands (opencode, bun, install scripts), so
+/// "command not found" problems can be diagnosed
+#[tauri::command]
+pub fn get_effective_path() -> String {
+    build_augmented_path()
+}
+
 #[tauri::command]
 pub async fn check_opencode_installation(app: AppHandle) -> OpenCodeCheckResult {
     let command = match opencode_command(&app) {
@@ -362,193 +783,1091 @@ pub async fn check_opencode_installation(app: AppHandle) -> OpenCodeCheckResult
                 }
             }
         }
-        Err(e) => OpenCodeCheckResult {
-            installed: false,
-            version: None,
-            error: Some(format!("OpenCode CLI not found: {}", e)),
+        Err(e) => OpenCodeCheckResult {
+            installed: false,
+            version: None,
+            error: Some(format!("OpenCode CLI not found: {}", e)),
+        },
+    }
+}
+
+#[tauri::command]
+pub fn get_opencode_port(state: tauri::State<'_, AppState>) -> Option<u16> {
+    *state.opencode_port.lock().unwrap()
+}
+
+/// Ports below this need root on unix
+const MIN_USER_PORT: u16 = 1024;
+
+fn validate_opencode_port(port: u16) -> AppResult<()> {
+    if port < MIN_USER_PORT {
+        return Err(format!("Port {} is privileged; choose {} or higher", port, MIN_USER_PORT).into());
+    }
+    if !is_port_free(port) {
+        return Err(AppError::PortInUse(port));
+    }
+    Ok(())
+}
+
+/// Pin the OpenCode port. It's picked up on the next launch or restart, which
+/// reuse the persisted port while it's free.
+#[tauri::command]
+pub fn set_opencode_port(state: tauri::State<'_, AppState>, port: u16) -> AppResult<()> {
+    // The running server holds its own port, so re-selecting it is always fine
+    if *state.opencode_port.lock().unwrap() != Some(port) {
+        validate_opencode_port(port)?;
+    }
+    let mut settings = load_settings();
+    settings.opencode_port = Some(port);
+    save_settings(&settings)
+}
+
+/// Seconds since the tracked OpenCode server was started, or `None` if it isn't running
+#[tauri::command]
+pub fn get_opencode_uptime_secs(state: tauri::State<'_, AppState>) -> Option<u64> {
+    state
+        .opencode_started_at
+        .lock()
+        .unwrap()
+        .map(|started| started.elapsed().as_secs())
+}
+
+#[tauri::command]
+pub async fn start_opencode_server(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<u16> {
+    if state.opencode_pid.lock().unwrap().is_some() {
+        if let Some(port) = *state.opencode_port.lock().unwrap() {
+            return Ok(port);
+        }
+    }
+
+    let port = state
+        .opencode_port
+        .lock()
+        .unwrap()
+        .ok_or_else(|| AppError::Custom("OpenCode port not initialized".to_string()))?;
+
+    if let Some(pid) = *state.other_instance_pid.lock().unwrap() {
+        return Err(AppError::ServerStart(format!(
+            "Another Dilag instance (pid {}) is already running",
+            pid
+        )));
+    }
+
+    if !is_port_free(port) {
+        return Err(AppError::PortInUse(port));
+    }
+
+    fs::create_dir_all(get_sessions_dir())?;
+    ensure_config_exists()?;
+
+    let (cmd, source) = opencode_command(&app)?;
+
+    let dilag_dir = get_dilag_dir();
+    let augmented_path = build_augmented_path();
+    let data_home = isolated_data_home();
+    let hostname = if load_settings().bind_lan {
+        println!("[start_opencode_server] Warning: {}", LAN_BINDING_WARNING);
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+    println!(
+        "[start_opencode_server] Starting ({}) on port {} with XDG_CONFIG_HOME={:?}, XDG_DATA_HOME={:?}",
+        source, port, dilag_dir, data_home
+    );
+
+    let cmd = match data_home {
+        Some(data_home) => cmd.env("XDG_DATA_HOME", data_home.to_string_lossy().to_string()),
+        None => cmd,
+    };
+    let (_rx, child) = cmd
+        .args([
+            "serve",
+            "--port",
+            &port.to_string(),
+            "--hostname",
+            hostname,
+        ])
+        .env("XDG_CONFIG_HOME", dilag_dir.to_string_lossy().to_string())
+        .env("PATH", augmented_path)
+        // Prevent opencode from auto-loading the user's ~/.claude/CLAUDE.md and any
+        // project-level CLAUDE.md into our design agent's context. Their content is
+        // written for coding assistants and conflicts with our design-only workflow.
+        // See: opencode/src/session/instruction.ts (FILES array + globalFiles()).
+        .env("OPENCODE_DISABLE_CLAUDE_CODE_PROMPT", "1")
+        .spawn()
+        .map_err(|e| AppError::ServerStart(e.to_string()))?;
+
+    *state.opencode_pid.lock().unwrap() = Some(child.pid());
+    *state.opencode_started_at.lock().unwrap() = Some(std::time::Instant::now());
+    *state.opencode_last_activity.lock().unwrap() = Some(std::time::Instant::now());
+    *state.opencode_idle_stopped.lock().unwrap() = false;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    Ok(port)
+}
+
+#[tauri::command]
+pub async fn stop_opencode_server(state: tauri::State<'_, AppState>) -> AppResult<()> {
+    state.opencode_started_at.lock().unwrap().take();
+    let mut pid_guard = state.opencode_pid.lock().unwrap();
+    if let Some(pid) = pid_guard.take() {
+        kill_process(pid);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn restart_opencode_server(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<u16> {
+    println!("[restart_opencode_server] Starting restart...");
+
+    let old_pid = state.opencode_pid.lock().unwrap().take();
+    state.opencode_started_at.lock().unwrap().take();
+    if let Some(pid) = old_pid {
+        println!("[restart_opencode_server] Killing tracked process {}", pid);
+        kill_process(pid);
+
+        let started = std::time::Instant::now();
+        while is_process_alive(pid) && started.elapsed() < PROCESS_EXIT_TIMEOUT {
+            tokio::time::sleep(PROCESS_EXIT_POLL).await;
+        }
+        if is_process_alive(pid) {
+            println!(
+                "[restart_opencode_server] Process {} still alive after {:?}, continuing",
+                pid,
+                started.elapsed()
+            );
+        } else {
+            println!(
+                "[restart_opencode_server] Process {} exited after {:?}",
+                pid,
+                started.elapsed()
+            );
+        }
+    }
+
+    // Keep the port the user pinned (or the one just released); only move if it's taken
+    let tracked_port = *state.opencode_port.lock().unwrap();
+    let new_port = resolve_port(
+        &[load_settings().opencode_port, tracked_port],
+        "restart_opencode_server",
+    );
+    *state.opencode_port.lock().unwrap() = Some(new_port);
+    println!("[restart_opencode_server] Port: {}", new_port);
+
+    if let Some(cache_path) = models_cache_path() {
+        if cache_path.exists() {
+            println!("[restart_opencode_server] Deleting cache: {:?}", cache_path);
+            let _ = fs::remove_file(cache_path);
+        }
+    }
+
+    start_opencode_server(app, state).await
+}
+
+/// OpenCode's cached model list
+fn models_cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("opencode").join("models.json"))
+}
+
+/// Delete OpenCode's model cache so the list is refetched (e.g. after adding a
+/// provider) without restarting the server. Returns whether a file was removed.
+#[tauri::command]
+pub fn clear_models_cache() -> AppResult<bool> {
+    let Some(cache_path) = models_cache_path() else {
+        return Ok(false);
+    };
+    match fs::remove_file(&cache_path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub fn is_opencode_running(state: tauri::State<'_, AppState>) -> bool {
+    state.opencode_pid.lock().unwrap().is_some()
+}
+
+/// How often the idle monitor checks the server
+const IDLE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether the server has been idle longer than `timeout_mins`
+fn is_idle(last_activity: Option<std::time::Instant>, timeout_mins: u32) -> bool {
+    last_activity.is_some_and(|t| t.elapsed().as_secs() >= u64::from(timeout_mins) * 60)
+}
+
+/// Background thread that stops the server once it has been idle for the
+/// configured timeout, emitting `opencode:idle-stopped`. The setting is
+/// re-read on every check, so toggling it takes effect without a restart.
+pub fn spawn_idle_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        let Some(timeout_mins) = load_settings().opencode_idle_timeout_mins else {
+            continue;
+        };
+
+        let state = app.state::<AppState>();
+        let Some(pid) = *state.opencode_pid.lock().unwrap() else {
+            continue;
+        };
+        if !is_idle(*state.opencode_last_activity.lock().unwrap(), timeout_mins) {
+            continue;
+        }
+
+        println!("[idle] OpenCode idle for {} min, stopping {}", timeout_mins, pid);
+        kill_tracked_server(&state);
+        *state.opencode_idle_stopped.lock().unwrap() = true;
+
+        // Let the port free up so the next activity can restart on it
+        let started = std::time::Instant::now();
+        while is_process_alive(pid) && started.elapsed() < PROCESS_EXIT_TIMEOUT {
+            std::thread::sleep(PROCESS_EXIT_POLL);
+        }
+        let _ = app.emit("opencode:idle-stopped", pid);
+    });
+}
+
+/// Record user activity for the idle auto-stop. If the server was idle-stopped
+/// it is started again; returns the port in that case.
+#[tauri::command]
+pub async fn mark_opencode_activity(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Option<u16>> {
+    *state.opencode_last_activity.lock().unwrap() = Some(std::time::Instant::now());
+    let idle_stopped = *state.opencode_idle_stopped.lock().unwrap();
+    if !idle_stopped {
+        return Ok(None);
+    }
+    println!("[idle] Activity after idle stop, restarting OpenCode");
+    start_opencode_server(app, state).await.map(Some)
+}
+
+#[tauri::command]
+pub fn get_opencode_idle_timeout() -> Option<u32> {
+    load_settings().opencode_idle_timeout_mins
+}
+
+/// Set the idle auto-stop timeout in minutes; `None` (the default) disables it
+#[tauri::command]
+pub fn set_opencode_idle_timeout(minutes: Option<u32>) -> AppResult<()> {
+    if minutes == Some(0) {
+        return Err("Idle timeout must be at least 1 minute".into());
+    }
+    let mut settings = load_settings();
+    settings.opencode_idle_timeout_mins = minutes;
+    save_settings(&settings)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub name: String,
+}
+
+/// Flatten the server's `GET /provider` response into models from connected providers
+fn parse_provider_models(response: &serde_json::Value) -> Vec<ModelInfo> {
+    let connected: HashSet<&str> = response["connected"]
+        .as_array()
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut models = Vec::new();
+    for provider in response["all"].as_array().into_iter().flatten() {
+        let Some(provider_id) = provider["id"].as_str() else {
+            continue;
+        };
+        if !connected.contains(provider_id) {
+            continue;
+        }
+        let provider_name = provider["name"].as_str().unwrap_or(provider_id);
+        for (model_id, model) in provider["models"].as_object().into_iter().flatten() {
+            models.push(ModelInfo {
+                id: model_id.clone(),
+                provider_id: provider_id.to_string(),
+                provider_name: provider_name.to_string(),
+                name: model["name"].as_str().unwrap_or(model_id).to_string(),
+            });
+        }
+    }
+
+    models.sort_by(|a, b| (&a.provider_id, &a.name).cmp(&(&b.provider_id, &b.name)));
+    models
+}
+
+/// Models available through the user's connected providers, queried live from
+/// the running server so the list always reflects its current auth and cache
+#[tauri::command]
+pub async fn list_opencode_models(state: tauri::State<'_, AppState>) -> AppResult<Vec<ModelInfo>> {
+    if state.opencode_pid.lock().unwrap().is_none() {
+        return Err(AppError::ServerNotRunning);
+    }
+    let port = (*state.opencode_port.lock().unwrap()).ok_or(AppError::ServerNotRunning)?;
+
+    let response: serde_json::Value = reqwest::Client::new()
+        .get(format!("http://127.0.0.1:{}/provider", port))
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| AppError::Custom(format!("Failed to reach OpenCode server: {}", e)))?
+        .error_for_status()
+        .map_err(|e| AppError::Custom(format!("OpenCode server error: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Custom(format!("Invalid provider response: {}", e)))?;
+
+    Ok(parse_provider_models(&response))
+}
+
+/// Round trips slower than this are reported as slow
+const SLOW_PING_MS: u64 = 1000;
+/// Give up on a ping after this long; a server that can't answer is stuck
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PingResult {
+    pub latency_ms: u64,
+    pub status: u16,
+    pub slow: bool,
+}
+
+/// Time a `GET /health` round trip to the running server. Errors with
+/// `ServerNotRunning` when no server is tracked, so callers can tell that
+/// apart from a server that's up but slow or unresponsive.
+#[tauri::command]
+pub async fn ping_opencode(state: tauri::State<'_, AppState>) -> AppResult<PingResult> {
+    if state.opencode_pid.lock().unwrap().is_none() {
+        return Err(AppError::ServerNotRunning);
+    }
+    let port = (*state.opencode_port.lock().unwrap()).ok_or(AppError::ServerNotRunning)?;
+
+    let started = std::time::Instant::now();
+    let response = reqwest::Client::new()
+        .get(format!("http://127.0.0.1:{}/health", port))
+        .timeout(PING_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                AppError::Custom(format!(
+                    "OpenCode server is running but didn't respond within {}s",
+                    PING_TIMEOUT.as_secs()
+                ))
+            } else {
+                AppError::Custom(format!("Failed to reach OpenCode server: {}", e))
+            }
+        })?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(PingResult {
+        latency_ms,
+        status: response.status().as_u16(),
+        slow: latency_ms > SLOW_PING_MS,
+    })
+}
+
+/// Upper bound on lines returned by `read_opencode_log`
+const MAX_LOG_LINES: usize = 2000;
+/// Only the tail of the log is read, so huge logs don't get loaded whole
+const MAX_LOG_TAIL_BYTES: u64 = 512 * 1024;
+
+/// `XDG_DATA_HOME` to give OpenCode when data isolation is on, `None` to share
+/// the user's own OpenCode data
+fn isolated_data_home() -> Option<PathBuf> {
+    load_settings()
+        .isolate_opencode_data
+        .then(get_opencode_data_home)
+}
+
+/// OpenCode's data directory. It follows XDG on every platform, so this is
+/// `~/.local/share/opencode` unless `XDG_DATA_HOME` is set or data is isolated.
+fn opencode_data_dir() -> Option<PathBuf> {
+    let data_home = isolated_data_home()
+        .or_else(|| std::env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+        .filter(|p| p.is_absolute())
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))?;
+    Some(data_home.join("opencode"))
+}
+
+/// Most recently modified `.log` file in OpenCode's log directory
+fn latest_opencode_log() -> Option<PathBuf> {
+    let log_dir = opencode_data_dir()?.join("log");
+    fs::read_dir(log_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "log"))
+        .max_by_key(|path| {
+            fs::metadata(path)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::UNIX_EPOCH)
+        })
+}
+
+/// Return the last `lines` lines (capped at 2000) of OpenCode's own log file,
+/// with ANSI codes stripped. Returns an empty list when no log exists yet.
+#[tauri::command]
+pub fn read_opencode_log(lines: usize) -> AppResult<Vec<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let Some(path) = latest_opencode_log() else {
+        return Ok(vec![]);
+    };
+
+    let mut file = fs::File::open(&path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(MAX_LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+
+    let text = strip_ansi(&String::from_utf8_lossy(&buf));
+    let mut all: Vec<&str> = text.lines().collect();
+    // Drop the partial first line when we started mid-file
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+
+    let count = lines.min(MAX_LOG_LINES);
+    let skip = all.len().saturating_sub(count);
+    Ok(all[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallProgress {
+    pub stage: String,
+    pub message: String,
+    pub completed: bool,
+    pub error: Option<String>,
+}
+
+/// Emit an `install:progress` event so the setup UI can show live feedback
+fn emit_install_progress(app: &AppHandle, stage: &str, message: &str) {
+    let _ = app.emit(
+        "install:progress",
+        InstallProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            completed: false,
+            error: None,
+        },
+    );
+}
+
+/// Official install script for a dependency, with its pinned digest
+struct InstallScript {
+    stage: &'static str,
+    url: &'static str,
+    /// Lowercase hex SHA-256 of the vetted script revision. Optional: without
+    /// a pin the script runs unverified and a warning is logged.
+    sha256: Option<&'static str>,
+}
+
+const BUN_INSTALL_SCRIPT: InstallScript = InstallScript {
+    stage: "bun",
+    url: "https://bun.sh/install",
+    sha256: None,
+};
+
+const OPENCODE_INSTALL_SCRIPT: InstallScript = InstallScript {
+    stage: "opencode",
+    url: "https://opencode.ai/install",
+    sha256: None,
+};
+
+/// Escape hatch for when upstream rotates a script before the pin is updated
+const SKIP_INSTALL_CHECKSUM_ENV: &str = "DILAG_SKIP_INSTALL_CHECKSUM";
+
+/// Compare a script's SHA-256 against the pinned digest. Only an actual
+/// mismatch fails; there is nothing to check when no digest is pinned.
+fn verify_sha256(bytes: &[u8], expected: Option<&str>) -> Result<(), String> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Install script checksum mismatch (expected {}, got {}). Set {}=1 to bypass.",
+            expected, actual, SKIP_INSTALL_CHECKSUM_ENV
+        ))
+    }
+}
+
+/// Download an install script to a temp file and verify it before returning
+/// it, so a tampered payload is never handed to bash. The file is created
+/// exclusively under a random name (mode 0600) and removed when dropped, so
+/// keep it alive until bash has run.
+async fn download_install_script(
+    app: &AppHandle,
+    state: &AppState,
+    script: &InstallScript,
+) -> Result<tempfile::NamedTempFile, String> {
+    let file = tempfile::Builder::new()
+        .prefix(&format!("dilag-{}-install-", script.stage))
+        .suffix(".sh")
+        .tempfile()
+        .map_err(|e| format!("Failed to create install script file: {}", e))?;
+    let path_str = file.path().to_string_lossy().to_string();
+
+    // Spawned rather than `.output()` so the PID is available to cancel a stuck download
+    let (mut rx, child) = app
+        .shell()
+        .command("curl")
+        .args(["-fsSL", script.url, "-o", &path_str])
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    *state.install_pid.lock().unwrap() = Some(child.pid());
+
+    let mut stderr = String::new();
+    let mut exit_code = None;
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stderr(bytes) => stderr.push_str(&String::from_utf8_lossy(&bytes)),
+            CommandEvent::Terminated(payload) => {
+                exit_code = payload.code;
+                break;
+            }
+            _ => {}
+        }
+    }
+    state.install_pid.lock().unwrap().take();
+
+    if exit_code != Some(0) {
+        return Err(format!("Failed to download {}: {}", script.url, stderr.trim()));
+    }
+
+    let bytes =
+        fs::read(file.path()).map_err(|e| format!("Failed to read install script: {}", e))?;
+    let skip = std::env::var(SKIP_INSTALL_CHECKSUM_ENV).is_ok_and(|v| v == "1");
+    if script.sha256.is_none() {
+        println!("[install] No checksum pinned for {}, running it unverified", script.url);
+    } else if !skip {
+        verify_sha256(&bytes, script.sha256)?;
+    }
+
+    Ok(file)
+}
+
+/// Download, verify, and run an install script, forwarding each output line as
+/// an `install:progress` event. Returns the tail of stderr when the script fails.
+async fn run_install_step(
+    app: &AppHandle,
+    state: &AppState,
+    script: &InstallScript,
+) -> Result<(), String> {
+    let stage = script.stage;
+    let script_file = download_install_script(app, state, script).await?;
+
+    let (mut rx, child) = app
+        .shell()
+        .command("bash")
+        .args([script_file.path().to_string_lossy().to_string()])
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    *state.install_pid.lock().unwrap() = Some(child.pid());
+
+    let mut stderr_tail: Vec<String> = Vec::new();
+
+    let result = loop {
+        let Some(event) = rx.recv().await else {
+            break Err("Install script ended without an exit status".to_string());
+        };
+        match event {
+            CommandEvent::Stdout(bytes) => {
+                for line in clean_output_lines(&String::from_utf8_lossy(&bytes)) {
+                    emit_install_progress(app, stage, &line);
+                }
+            }
+            CommandEvent::Stderr(bytes) => {
+                // curl and the install scripts write progress to stderr too
+                for line in clean_output_lines(&String::from_utf8_lossy(&bytes)) {
+                    emit_install_progress(app, stage, &line);
+                    stderr_tail.push(line);
+                }
+                if stderr_tail.len() > 20 {
+                    stderr_tail.drain(..stderr_tail.len() - 20);
+                }
+            }
+            CommandEvent::Terminated(payload) => {
+                break if payload.code == Some(0) {
+                    Ok(())
+                } else if stderr_tail.is_empty() {
+                    Err(format!("Install script exited with code {:?}", payload.code))
+                } else {
+                    Err(stderr_tail.join("\n"))
+                };
+            }
+            CommandEvent::Error(e) => break Err(e),
+            _ => {}
+        }
+    };
+
+    state.install_pid.lock().unwrap().take();
+    drop(script_file);
+    result
+}
+
+/// Outcome for a step that failed or was interrupted by `cancel_install_dependencies`.
+/// A cancelled step may have left a partial install behind, so it is never reported as done.
+fn install_step_failed(state: &AppState, stage: &str, name: &str, error: String) -> InstallProgress {
+    if *state.install_cancelled.lock().unwrap() {
+        InstallProgress {
+            stage: stage.to_string(),
+            message: format!("{} installation cancelled", name),
+            completed: false,
+            error: Some(format!(
+                "Installation was cancelled; {} may be partially installed",
+                name
+            )),
+        }
+    } else {
+        InstallProgress {
+            stage: stage.to_string(),
+            message: format!("Failed to install {}", name),
+            completed: false,
+            error: Some(error),
+        }
+    }
+}
+
+/// Install OpenCode and Bun using their official install scripts.
+///
+/// Streams `install:progress` events while the scripts run; the returned
+/// value is the final outcome, as before.
+#[tauri::command]
+pub async fn install_dependencies(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<InstallProgress, String> {
+    *state.install_cancelled.lock().unwrap() = false;
+
+    // Both scripts install under the home directory (~/.bun, ~/.opencode)
+    if let Some(home) = dirs::home_dir() {
+        if let Err(e) = crate::app_info::ensure_free_space(&home) {
+            return Ok(InstallProgress {
+                stage: "bun".to_string(),
+                message: "Not enough disk space to install dependencies".to_string(),
+                completed: false,
+                error: Some(e.to_string()),
+            });
+        }
+    }
+
+    // First install Bun
+    emit_install_progress(&app, "bun", "Downloading Bun");
+    if let Err(e) = run_install_step(&app, &state, &BUN_INSTALL_SCRIPT).await {
+        return Ok(install_step_failed(&state, "bun", "Bun", e));
+    }
+    emit_install_progress(&app, "bun", "Bun installed");
+
+    if *state.install_cancelled.lock().unwrap() {
+        return Ok(install_step_failed(&state, "opencode", "OpenCode", String::new()));
+    }
+
+    // Then install OpenCode
+    emit_install_progress(&app, "opencode", "Downloading OpenCode");
+    if let Err(e) = run_install_step(&app, &state, &OPENCODE_INSTALL_SCRIPT).await {
+        return Ok(install_step_failed(&state, "opencode", "OpenCode", e));
+    }
+    emit_install_progress(&app, "opencode", "OpenCode installed");
+
+    let result = InstallProgress {
+        stage: "complete".to_string(),
+        message: "All dependencies installed successfully".to_string(),
+        completed: true,
+        error: None,
+    };
+    let _ = app.emit("install:progress", result.clone());
+    Ok(result)
+}
+
+/// Abort a running `install_dependencies` by killing its current child process
+/// and everything it spawned. Returns whether an install step was running.
+#[tauri::command]
+pub fn cancel_install_dependencies(app: AppHandle, state: tauri::State<'_, AppState>) -> bool {
+    *state.install_cancelled.lock().unwrap() = true;
+    let pid = state.install_pid.lock().unwrap().take();
+    if let Some(pid) = pid {
+        println!("[install_dependencies] Cancelling, killing {} and its children", pid);
+        kill_process_tree(pid);
+    }
+    let _ = app.emit("install:cancelled", ());
+    pid.is_some()
+}
+
+// =============================================================================
+// Auth Commands
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthStatus {
+    pub authenticated: bool,
+    pub providers: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Parse `opencode auth list` output into provider names.
+///
+/// Entries look like `● Anthropic oauth` under "Credentials" and
+/// `● OpenAI OPENAI_API_KEY` under "Environment"; the trailing token
+/// (auth type or env var) is dropped.
+fn parse_auth_list(raw_output: &str) -> Vec<String> {
+    let clean = strip_ansi(raw_output);
+    let mut providers = Vec::new();
+    let mut in_section = false;
+
+    for line in clean.lines() {
+        let content = line
+            .trim()
+            .trim_start_matches('┌')
+            .trim_start_matches('│')
+            .trim();
+
+        if content.starts_with("Credentials") || content.starts_with("Environment") {
+            in_section = true;
+            continue;
+        }
+        if line.trim().starts_with('└') {
+            in_section = false;
+            continue;
+        }
+        if !in_section || content.is_empty() {
+            continue;
+        }
+
+        let mut words: Vec<&str> = content.split_whitespace().collect();
+        if words.len() > 1 {
+            let last = words[words.len() - 1];
+            let is_env_var = last
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            if is_env_var || matches!(last, "oauth" | "api" | "wellknown") {
+                words.pop();
+            }
+        }
+        let name = words.join(" ");
+        if !providers.contains(&name) {
+            providers.push(name);
+        }
+    }
+
+    providers
+}
+
+/// Check which providers OpenCode is authenticated with via `opencode auth list`
+#[tauri::command]
+pub async fn check_opencode_auth(app: AppHandle) -> AuthStatus {
+    let command = match opencode_command(&app) {
+        Ok((cmd, _)) => cmd,
+        Err(e) => {
+            return AuthStatus {
+                authenticated: false,
+                providers: vec![],
+                error: Some(e.to_string()),
+            };
+        }
+    };
+
+    let command = match isolated_data_home() {
+        Some(data_home) => command.env("XDG_DATA_HOME", data_home.to_string_lossy().to_string()),
+        None => command,
+    };
+    match command
+        .args(["auth", "list"])
+        .env("XDG_CONFIG_HOME", get_dilag_dir().to_string_lossy().to_string())
+        .env("PATH", build_augmented_path())
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => {
+            let providers = parse_auth_list(&String::from_utf8_lossy(&output.stdout));
+            AuthStatus {
+                authenticated: !providers.is_empty(),
+                providers,
+                error: None,
+            }
+        }
+        Ok(output) => {
+            let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr))
+                .trim()
+                .to_string();
+            AuthStatus {
+                authenticated: false,
+                providers: vec![],
+                error: if stderr.is_empty() { None } else { Some(stderr) },
+            }
+        }
+        Err(e) => AuthStatus {
+            authenticated: false,
+            providers: vec![],
+            error: Some(format!("Failed to run opencode auth list: {}", e)),
         },
     }
 }
 
+/// Open a Terminal window running `opencode auth login` (interactive, so it
+/// can't run headless through the shell plugin).
 #[tauri::command]
-pub fn get_opencode_port(state: tauri::State<'_, AppState>) -> Option<u16> {
-    *state.opencode_port.lock().unwrap()
+pub fn open_opencode_login() -> AppResult<()> {
+    #[cfg(target_os = "macos")]
+    {
+        // Prefer the bundled sidecar, which Tauri places next to the app binary
+        let sidecar = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("opencode")))
+            .filter(|path| path.is_file());
+        let binary = sidecar
+            .or_else(get_opencode_binary_path)
+            .ok_or(AppError::OpenCodeNotFound)?;
+
+        let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\\\''"));
+        let env_prefix = isolated_data_home()
+            .map(|home| format!("XDG_DATA_HOME={} ", quote(&home.to_string_lossy())))
+            .unwrap_or_default();
+        let script = format!(
+            "tell application \"Terminal\"\n activate\n do script \"{}{} auth login\"\nend tell",
+            env_prefix,
+            quote(&binary.to_string_lossy())
+        );
+        std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .spawn()
+            .map_err(|e| AppError::Custom(format!("Failed to open Terminal: {}", e)))?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err(AppError::Custom(
+            "Run `opencode auth login` in a terminal to sign in".to_string(),
+        ))
+    }
 }
 
+// =============================================================================
+// Permission Commands
+// =============================================================================
+
+/// Get the effective bash permission map (defaults merged with user overrides)
 #[tauri::command]
-pub async fn start_opencode_server(
-    app: AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> AppResult<u16> {
-    if state.opencode_pid.lock().unwrap().is_some() {
-        if let Some(port) = *state.opencode_port.lock().unwrap() {
-            return Ok(port);
+pub fn get_bash_permissions() -> HashMap<String, String> {
+    effective_bash_permissions(&load_settings().bash_permissions)
+        .into_iter()
+        .map(|(k, v)| (k, v.as_str().unwrap_or_default().to_string()))
+        .collect()
+}
+
+/// Persist bash permission overrides. Values must be `allow`, `ask`, or `deny`.
+/// Takes effect the next time the OpenCode server is (re)started, since the
+/// config is only written on start.
+#[tauri::command]
+pub fn set_bash_permissions(
+    permissions: HashMap<String, String>,
+) -> AppResult<HashMap<String, String>> {
+    for (pattern, action) in &permissions {
+        if pattern.trim().is_empty() {
+            return Err(AppError::Custom("Permission pattern cannot be empty".to_string()));
+        }
+        if !PERMISSION_ACTIONS.contains(&action.as_str()) {
+            return Err(AppError::Custom(format!(
+                "Invalid permission \"{}\" for \"{}\" (expected allow, ask, or deny)",
+                action, pattern
+            )));
         }
     }
 
-    let port = state
-        .opencode_port
-        .lock()
-        .unwrap()
-        .ok_or_else(|| AppError::Custom("OpenCode port not initialized".to_string()))?;
-
-    fs::create_dir_all(get_sessions_dir())?;
-    ensure_config_exists()?;
+    let mut settings = load_settings();
+    settings.bash_permissions = permissions;
+    save_settings(&settings)?;
 
-    let (cmd, source) = opencode_command(&app)?;
+    Ok(get_bash_permissions())
+}
 
-    let dilag_dir = get_dilag_dir();
-    let augmented_path = build_augmented_path();
-    println!(
-        "[start_opencode_server] Starting ({}) on port {} with XDG_CONFIG_HOME={:?}",
-        source, port, dilag_dir
-    );
+/// Drop all user overrides and revert to the built-in bash permissions.
+/// Takes effect on the next server (re)start.
+#[tauri::command]
+pub fn reset_bash_permissions() -> AppResult<HashMap<String, String>> {
+    let mut settings = load_settings();
+    settings.bash_permissions.clear();
+    save_settings(&settings)?;
 
-    let (_rx, child) = cmd
-        .args([
-            "serve",
-            "--port",
-            &port.to_string(),
-            "--hostname",
-            "127.0.0.1",
-        ])
-        .env("XDG_CONFIG_HOME", dilag_dir.to_string_lossy().to_string())
-        .env("PATH", augmented_path)
-        // Prevent opencode from auto-loading the user's ~/.claude/CLAUDE.md and any
-        // project-level CLAUDE.md into our design agent's context. Their content is
-        // written for coding assistants and conflicts with our design-only workflow.
-        // See: opencode/src/session/instruction.ts (FILES array + globalFiles()).
-        .env("OPENCODE_DISABLE_CLAUDE_CODE_PROMPT", "1")
-        .spawn()
-        .map_err(|e| AppError::ServerStart(e.to_string()))?;
+    Ok(get_bash_permissions())
+}
 
-    *state.opencode_pid.lock().unwrap() = Some(child.pid());
+// =============================================================================
+// OpenCode Option Commands
+// =============================================================================
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenCodeOptionsUpdate {
+    pub options: serde_json::Map<String, serde_json::Value>,
+    /// True when a running server has to be restarted to pick up the change
+    pub restart_required: bool,
+}
 
-    Ok(port)
+/// Get the effective `share` / `autoupdate` options (defaults merged with user overrides)
+#[tauri::command]
+pub fn get_opencode_options() -> serde_json::Map<String, serde_json::Value> {
+    effective_opencode_options(&load_settings().opencode_options)
 }
 
+/// Override a top-level OpenCode option. Passing `null` reverts it to the default.
+/// The config is only written on start, so a running server needs a restart.
 #[tauri::command]
-pub async fn stop_opencode_server(state: tauri::State<'_, AppState>) -> AppResult<()> {
-    let mut pid_guard = state.opencode_pid.lock().unwrap();
-    if let Some(pid) = pid_guard.take() {
-        kill_process(pid);
+pub fn set_opencode_option(
+    state: tauri::State<'_, AppState>,
+    key: String,
+    value: serde_json::Value,
+) -> AppResult<OpenCodeOptionsUpdate> {
+    validate_opencode_option(&key, &value)?;
+
+    let mut settings = load_settings();
+    if value.is_null() {
+        settings.opencode_options.remove(&key);
+    } else {
+        settings.opencode_options.insert(key, value);
     }
-    Ok(())
+    save_settings(&settings)?;
+
+    Ok(OpenCodeOptionsUpdate {
+        options: effective_opencode_options(&settings.opencode_options),
+        restart_required: state.opencode_pid.lock().unwrap().is_some(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanAddress {
+    pub ip: String,
+    pub port: u16,
+    pub url: String,
+    pub warning: String,
 }
 
+/// Kill whatever holds one of the app's ports (e.g. an orphaned server after a
+/// crash). Only the current and persisted OpenCode ports are accepted.
+/// Returns how many processes were killed.
 #[tauri::command]
-pub async fn restart_opencode_server(
-    app: AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> AppResult<u16> {
-    println!("[restart_opencode_server] Starting restart...");
+pub fn free_port(state: tauri::State<'_, AppState>, port: u16) -> AppResult<u32> {
+    let current = *state.opencode_port.lock().unwrap();
+    let persisted = load_settings().opencode_port;
+    if current != Some(port) && persisted != Some(port) {
+        return Err(format!("Port {} is not used by Dilag", port).into());
+    }
 
-    {
-        let mut pid_guard = state.opencode_pid.lock().unwrap();
-        if let Some(pid) = pid_guard.take() {
-            println!("[restart_opencode_server] Killing tracked process {}", pid);
+    Ok(kill_port_holders(&state, port).len() as u32)
+}
+
+/// Kill every process (other than Dilag itself) holding `port`, clearing the
+/// tracked server if it's one of them. Returns the killed PIDs.
+fn kill_port_holders(state: &AppState, port: u16) -> Vec<u32> {
+    let tracked = *state.opencode_pid.lock().unwrap();
+    let own_pid = std::process::id();
+    let mut killed = Vec::new();
+    for pid in pids_on_port(port).into_iter().filter(|pid| *pid != own_pid) {
+        if tracked == Some(pid) {
+            kill_tracked_server(state);
+        } else {
             kill_process(pid);
         }
+        println!("[opencode] Killed {} holding port {}", pid, port);
+        killed.push(pid);
     }
+    killed
+}
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+#[derive(Debug, Serialize)]
+pub struct StopAllSummary {
+    /// PID of the tracked OpenCode server, if one was running
+    pub opencode_pid: Option<u32>,
+    /// Other processes killed for still holding the OpenCode port
+    pub port_pids_killed: u32,
+    pub watchers_stopped: u32,
+}
 
-    let new_port = get_free_port();
-    *state.opencode_port.lock().unwrap() = Some(new_port);
-    println!("[restart_opencode_server] New port: {}", new_port);
+/// Stop the OpenCode server, anything left on its port, and all session
+/// watchers. Safe to call when nothing is running.
+#[tauri::command]
+pub fn stop_all_servers(state: tauri::State<'_, AppState>) -> StopAllSummary {
+    let opencode_pid = *state.opencode_pid.lock().unwrap();
+    let port = *state.opencode_port.lock().unwrap();
+    let port_pids_killed = port
+        .map(|port| kill_port_holders(&state, port))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|pid| Some(*pid) != opencode_pid)
+        .count() as u32;
+    // The tracked server may not have bound its port yet
+    kill_tracked_server(&state);
+
+    let watchers_stopped = {
+        let mut watchers = state.session_watchers.lock().unwrap();
+        let count = watchers.len() as u32;
+        watchers.clear();
+        count
+    };
 
-    if let Some(cache_path) = dirs::cache_dir().map(|p| p.join("opencode").join("models.json")) {
-        if cache_path.exists() {
-            println!("[restart_opencode_server] Deleting cache: {:?}", cache_path);
-            let _ = fs::remove_file(cache_path);
-        }
+    StopAllSummary {
+        opencode_pid,
+        port_pids_killed,
+        watchers_stopped,
     }
-
-    start_opencode_server(app, state).await
 }
 
 #[tauri::command]
-pub fn is_opencode_running(state: tauri::State<'_, AppState>) -> bool {
-    state.opencode_pid.lock().unwrap().is_some()
+pub fn get_bind_lan() -> bool {
+    load_settings().bind_lan
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct InstallProgress {
-    pub stage: String,
-    pub message: String,
-    pub completed: bool,
-    pub error: Option<String>,
+/// Toggle LAN binding. Takes effect on the next server (re)start.
+#[tauri::command]
+pub fn set_bind_lan(enabled: bool) -> AppResult<()> {
+    let mut settings = load_settings();
+    settings.bind_lan = enabled;
+    save_settings(&settings)
 }
 
-/// Install OpenCode and Bun using their official install scripts
+/// Address other devices can use to reach the server, when LAN binding is on
+/// and the machine has a LAN IPv4 address
 #[tauri::command]
-pub async fn install_dependencies(app: AppHandle) -> Result<InstallProgress, String> {
-    let shell = app.shell();
-
-    // First install Bun
-    let bun_install = shell
-        .command("bash")
-        .args(["-c", "curl -fsSL https://bun.sh/install | bash"])
-        .output()
-        .await;
-
-    match bun_install {
-        Ok(output) if !output.status.success() => {
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            return Ok(InstallProgress {
-                stage: "bun".to_string(),
-                message: "Failed to install Bun".to_string(),
-                completed: false,
-                error: Some(stderr),
-            });
-        }
-        Err(e) => {
-            return Ok(InstallProgress {
-                stage: "bun".to_string(),
-                message: "Failed to install Bun".to_string(),
-                completed: false,
-                error: Some(e.to_string()),
-            });
-        }
-        _ => {}
+pub fn get_lan_address(state: tauri::State<'_, AppState>) -> Option<LanAddress> {
+    if !load_settings().bind_lan {
+        return None;
     }
+    let port = (*state.opencode_port.lock().unwrap())?;
+    let ip = primary_lan_ipv4()?;
+    Some(LanAddress {
+        ip: ip.to_string(),
+        port,
+        url: format!("http://{}:{}", ip, port),
+        warning: LAN_BINDING_WARNING.to_string(),
+    })
+}
 
-    // Then install OpenCode
-    let opencode_install = shell
-        .command("bash")
-        .args(["-c", "curl -fsSL https://opencode.ai/install | bash"])
-        .output()
-        .await;
-
-    match opencode_install {
-        Ok(output) if !output.status.success() => {
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            return Ok(InstallProgress {
-                stage: "opencode".to_string(),
-                message: "Failed to install OpenCode".to_string(),
-                completed: false,
-                error: Some(stderr),
-            });
-        }
-        Err(e) => {
-            return Ok(InstallProgress {
-                stage: "opencode".to_string(),
-                message: "Failed to install OpenCode".to_string(),
-                completed: false,
-                error: Some(e.to_string()),
-            });
-        }
-        _ => {}
-    }
+/// Whether OpenCode runs with its own data directory instead of sharing the user's
+#[tauri::command]
+pub fn get_opencode_data_isolation() -> bool {
+    load_settings().isolate_opencode_data
+}
 
-    Ok(InstallProgress {
-        stage: "complete".to_string(),
-        message: "All dependencies installed successfully".to_string(),
-        completed: true,
-        error: None,
-    })
+/// Toggle OpenCode data isolation. Takes effect on the next server (re)start;
+/// providers need to be signed in again inside the isolated data directory.
+#[tauri::command]
+pub fn set_opencode_data_isolation(enabled: bool) -> AppResult<()> {
+    let mut settings = load_settings();
+    settings.isolate_opencode_data = enabled;
+    save_settings(&settings)
 }
 
 // =============================================================================
@@ -560,6 +1879,8 @@ pub struct SkillInfo {
     pub name: String,
     pub path: String,
     pub is_symlink: bool,
+    /// Symlink whose target no longer exists (fix with `repair_skills`)
+    pub broken: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -580,6 +1901,8 @@ pub struct SkillInstallResult {
     pub success: bool,
     pub installed: Vec<String>,
     pub error: Option<String>,
+    /// ANSI-stripped CLI output, one entry per non-empty line
+    pub logs: Vec<String>,
 }
 
 /// Strip ANSI escape codes from a string.
@@ -640,6 +1963,29 @@ fn strip_ansi(s: &str) -> String {
     result
 }
 
+/// Split CLI output into clean, non-empty lines with ANSI codes and
+/// clack/prompts gutter characters (`│`) removed.
+fn clean_output_lines(raw_output: &str) -> Vec<String> {
+    strip_ansi(raw_output)
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['│', '|']).trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Pick out the lines that describe a failure from cleaned CLI output
+fn error_lines(lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("error") || lower.contains("failed") || lower.contains('✗')
+        })
+        .cloned()
+        .collect()
+}
+
 /// Parse the output of `npx skills add <source> -l` into skill name/description pairs.
 fn parse_skill_list(raw_output: &str) -> Vec<SkillPreview> {
     let clean = strip_ansi(raw_output);
@@ -714,7 +2060,10 @@ fn parse_skill_list(raw_output: &str) -> Vec<SkillPreview> {
 /// Checks `skill/` (OpenCode native) and `skills/` (skills.sh CLI convention).
 #[tauri::command]
 pub fn list_installed_skills() -> AppResult<Vec<SkillInfo>> {
-    let config_dir = get_opencode_config_dir();
+    Ok(list_skills_in(&get_opencode_config_dir()))
+}
+
+fn list_skills_in(config_dir: &Path) -> Vec<SkillInfo> {
     let mut skills = Vec::new();
     let mut seen = HashSet::new();
 
@@ -734,6 +2083,7 @@ pub fn list_installed_skills() -> AppResult<Vec<SkillInfo>> {
                                 name: name.to_string(),
                                 path: path.to_string_lossy().to_string(),
                                 is_symlink,
+                                broken: is_symlink && !path.exists(),
                             });
                         }
                     }
@@ -743,7 +2093,7 @@ pub fn list_installed_skills() -> AppResult<Vec<SkillInfo>> {
     }
 
     skills.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(skills)
+    skills
 }
 
 /// Validate that a skill source string is a safe owner/repo pattern or URL.
@@ -842,9 +2192,11 @@ pub async fn install_skill(
         .env("PATH", augmented_path)
         .output()
         .await
-        .map_err(|e| AppError::Custom(format!("Failed to run npx: {}", e)))?;
+        .map_err(|e| AppError::SkillInstallFailed(format!("Failed to run npx: {}", e)))?;
 
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout_lines = clean_output_lines(&String::from_utf8_lossy(&output.stdout));
+    let stderr_lines = clean_output_lines(&String::from_utf8_lossy(&output.stderr));
+    let logs: Vec<String> = stdout_lines.iter().chain(&stderr_lines).cloned().collect();
 
     if output.status.success() {
         sync_canonical_skills()?;
@@ -864,34 +2216,44 @@ pub async fn install_skill(
             success: true,
             installed: actually_installed,
             error: None,
+            logs,
         })
     } else {
+        let errors = error_lines(&logs);
+        let error = if !errors.is_empty() {
+            errors.join("\n")
+        } else if !stderr_lines.is_empty() {
+            stderr_lines.join("\n")
+        } else {
+            "Installation failed".to_string()
+        };
         Ok(SkillInstallResult {
             success: false,
             installed: vec![],
-            error: Some(if stderr.is_empty() {
-                "Installation failed".to_string()
-            } else {
-                stderr
-            }),
+            error: Some(error),
+            logs,
         })
     }
 }
 
 /// Sync skills from the canonical `~/.agents/skills/` directory into
 /// `~/.dilag/opencode/skill/` by creating symlinks for any missing skills.
-fn sync_canonical_skills() -> AppResult<()> {
+/// Returns the names of newly linked skills.
+fn sync_canonical_skills() -> AppResult<Vec<String>> {
     let home = dirs::home_dir().ok_or(AppError::Custom("No home directory".to_string()))?;
     let canonical_dir = home.join(".agents").join("skills");
-    let target_dir = get_opencode_config_dir().join("skill");
+    sync_skills_into(&canonical_dir, &get_opencode_config_dir().join("skill"))
+}
 
+fn sync_skills_into(canonical_dir: &Path, target_dir: &Path) -> AppResult<Vec<String>> {
+    let mut added = Vec::new();
     if !canonical_dir.exists() {
-        return Ok(());
+        return Ok(added);
     }
 
-    fs::create_dir_all(&target_dir)?;
+    fs::create_dir_all(target_dir)?;
 
-    if let Ok(entries) = fs::read_dir(&canonical_dir) {
+    if let Ok(entries) = fs::read_dir(canonical_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if !path.is_dir() {
@@ -899,7 +2261,8 @@ fn sync_canonical_skills() -> AppResult<()> {
             }
             if let Some(name) = path.file_name() {
                 let dest = target_dir.join(name);
-                if !dest.exists() {
+                // `symlink_metadata` so a dangling link counts as taken
+                if dest.symlink_metadata().is_err() {
                     #[cfg(unix)]
                     {
                         std::os::unix::fs::symlink(&path, &dest)?;
@@ -908,12 +2271,51 @@ fn sync_canonical_skills() -> AppResult<()> {
                     {
                         std::os::windows::fs::symlink_dir(&path, &dest)?;
                     }
+                    added.push(name.to_string_lossy().to_string());
                 }
             }
         }
     }
 
-    Ok(())
+    added.sort();
+    Ok(added)
+}
+
+/// Remove symlinks in `dir` whose target is gone. Returns their names.
+fn remove_dangling_links(dir: &Path) -> AppResult<Vec<String>> {
+    let mut removed = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(removed);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_symlink = entry.file_type().map(|ft| ft.is_symlink()).unwrap_or(false);
+        if is_symlink && !path.exists() {
+            fs::remove_file(&path)?;
+            removed.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillRepairReport {
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+/// Drop skill symlinks whose target was moved or deleted, then re-link
+/// anything missing from `~/.agents/skills/`
+#[tauri::command]
+pub fn repair_skills() -> AppResult<SkillRepairReport> {
+    let config_dir = get_opencode_config_dir();
+    let mut removed = Vec::new();
+    for dir_name in &["skill", "skills"] {
+        removed.extend(remove_dangling_links(&config_dir.join(dir_name))?);
+    }
+    let added = sync_canonical_skills()?;
+    Ok(SkillRepairReport { removed, added })
 }
 
 /// Remove an installed skill. Handles both symlinks (just remove the link)
@@ -934,6 +2336,36 @@ pub fn remove_skill(skill_name: String) -> AppResult<()> {
     Ok(())
 }
 
+/// Read the `opencode.json` Dilag generated, for inspection only
+#[tauri::command]
+pub fn get_opencode_config() -> AppResult<String> {
+    let config_file = get_opencode_config_dir().join("opencode.json");
+    if !config_file.exists() {
+        return Err(AppError::Custom(
+            "OpenCode config has not been generated yet. Start the server first.".to_string(),
+        ));
+    }
+    Ok(fs::read_to_string(&config_file)?)
+}
+
+/// Read an installed skill's `SKILL.md` from `skill/` or `skills/`
+#[tauri::command]
+pub fn get_skill_content(skill_name: String) -> AppResult<String> {
+    if skill_name.is_empty()
+        || !skill_name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::Custom(format!("Invalid skill name: {}", skill_name)));
+    }
+
+    let config_dir = get_opencode_config_dir();
+    let skill_file = ["skill", "skills"]
+        .iter()
+        .map(|dir| config_dir.join(dir).join(&skill_name).join("SKILL.md"))
+        .find(|p| p.is_file())
+        .ok_or_else(|| AppError::Custom(format!("Skill not found: {}", skill_name)))?;
+    Ok(fs::read_to_string(&skill_file)?)
+}
+
 /// Check if Bun is installed and get its version
 #[tauri::command]
 pub async fn check_bun_installation(app: AppHandle) -> BunCheckResult {
@@ -980,3 +2412,284 @@ pub async fn check_bun_installation(app: AppHandle) -> BunCheckResult {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_specs_must_be_pinned() {
+        assert_eq!(
+            parse_plugin_spec("opencode-antigravity-auth@1.2.8"),
+            Some(("opencode-antigravity-auth", "1.2.8"))
+        );
+        assert_eq!(
+            parse_plugin_spec("@acme/opencode-plugin@0.3.0-beta.1"),
+            Some(("@acme/opencode-plugin", "0.3.0-beta.1"))
+        );
+        assert_eq!(parse_plugin_spec("opencode-antigravity-auth"), None);
+        assert_eq!(parse_plugin_spec("@acme/opencode-plugin"), None);
+        assert_eq!(parse_plugin_spec("Bad Name@1.0.0"), None);
+        assert_eq!(parse_plugin_spec("plugin@"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_skill_links_are_flagged_and_repaired() {
+        let canonical = tempfile::tempdir().unwrap();
+        let config = tempfile::tempdir().unwrap();
+        let skill_dir = config.path().join("skill");
+        fs::create_dir_all(canonical.path().join("fresh")).unwrap();
+        fs::create_dir_all(&skill_dir).unwrap();
+        std::os::unix::fs::symlink(canonical.path().join("moved"), skill_dir.join("moved")).unwrap();
+
+        let skills = list_skills_in(config.path());
+        assert_eq!(skills.len(), 1);
+        assert!(skills[0].is_symlink && skills[0].broken);
+
+        assert_eq!(remove_dangling_links(&skill_dir).unwrap(), vec!["moved"]);
+        assert_eq!(sync_skills_into(canonical.path(), &skill_dir).unwrap(), vec!["fresh"]);
+        assert!(sync_skills_into(canonical.path(), &skill_dir).unwrap().is_empty());
+
+        let skills = list_skills_in(config.path());
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name, "fresh");
+        assert!(!skills[0].broken);
+    }
+
+    #[test]
+    fn idle_after_timeout_only() {
+        let now = std::time::Instant::now();
+        assert!(!is_idle(None, 1));
+        assert!(!is_idle(Some(now), 1));
+        let Some(long_ago) = now.checked_sub(std::time::Duration::from_secs(61)) else {
+            return;
+        };
+        assert!(is_idle(Some(long_ago), 1));
+        assert!(!is_idle(Some(long_ago), 2));
+    }
+
+    #[test]
+    fn opencode_port_validation() {
+        assert!(validate_opencode_port(80).is_err());
+        assert!(validate_opencode_port(MIN_USER_PORT - 1).is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = listener.local_addr().unwrap().port();
+        assert!(matches!(validate_opencode_port(taken), Err(AppError::PortInUse(p)) if p == taken));
+        drop(listener);
+        assert!(validate_opencode_port(taken).is_ok());
+    }
+
+    #[test]
+    fn lsof_output_yields_unique_pids() {
+        assert_eq!(parse_lsof_pids("4321\n1234\n4321\n\n"), vec![1234, 4321]);
+        assert!(parse_lsof_pids("").is_empty());
+    }
+
+    #[test]
+    fn netstat_output_matches_listening_port_only() {
+        let output = "
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:4096           0.0.0.0:0              LISTENING       5120
+  TCP    127.0.0.1:40960        0.0.0.0:0              LISTENING       6000
+  TCP    127.0.0.1:4096         127.0.0.1:51000        ESTABLISHED     5120
+  TCP    [::]:4096              [::]:0                 LISTENING       5120
+";
+        assert_eq!(parse_netstat_pids(output, 4096), vec![5120]);
+        assert!(parse_netstat_pids(output, 5000).is_empty());
+    }
+
+    #[test]
+    fn write_if_changed_skips_identical_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("opencode.json");
+
+        assert!(write_if_changed(&file, "{}").unwrap());
+        assert!(!write_if_changed(&file, "{}").unwrap());
+        assert!(write_if_changed(&file, "{\"a\":1}").unwrap());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "{\"a\":1}");
+    }
+
+    #[test]
+    fn provider_models_only_include_connected_providers() {
+        let response = serde_json::json!({
+            "connected": ["anthropic"],
+            "all": [
+                {
+                    "id": "anthropic",
+                    "name": "Anthropic",
+                    "models": {
+                        "claude-sonnet": { "name": "Claude Sonnet" },
+                        "claude-haiku": { "name": "Claude Haiku" }
+                    }
+                },
+                { "id": "openai", "name": "OpenAI", "models": { "gpt": { "name": "GPT" } } }
+            ]
+        });
+
+        let models = parse_provider_models(&response);
+        let names: Vec<_> = models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Claude Haiku", "Claude Sonnet"]);
+        assert!(models.iter().all(|m| m.provider_id == "anthropic"));
+    }
+
+    #[test]
+    fn opencode_options_default_and_override() {
+        let defaults = effective_opencode_options(&serde_json::Map::new());
+        assert_eq!(defaults["share"], "disabled");
+        assert_eq!(defaults["autoupdate"], false);
+
+        let mut overrides = serde_json::Map::new();
+        overrides.insert("share".to_string(), serde_json::json!("manual"));
+        overrides.insert("model".to_string(), serde_json::json!("ignored"));
+        let merged = effective_opencode_options(&overrides);
+        assert_eq!(merged["share"], "manual");
+        assert!(!merged.contains_key("model"));
+    }
+
+    #[test]
+    fn opencode_option_validation() {
+        assert!(validate_opencode_option("share", &serde_json::json!("auto")).is_ok());
+        assert!(validate_opencode_option("share", &serde_json::json!("public")).is_err());
+        assert!(validate_opencode_option("autoupdate", &serde_json::json!(true)).is_ok());
+        assert!(validate_opencode_option("autoupdate", &serde_json::json!("notify")).is_ok());
+        assert!(validate_opencode_option("autoupdate", &serde_json::json!(1)).is_err());
+        assert!(validate_opencode_option("share", &serde_json::Value::Null).is_ok());
+        assert!(validate_opencode_option("model", &serde_json::json!("x")).is_err());
+    }
+
+    // -------- skill install output --------
+
+    #[test]
+    fn cleans_ansi_laden_install_output() {
+        let raw = "\x1b[32m◇\x1b[0m  Installing skills\r\n│\n│  \x1b[1mfrontend-design\x1b[22m installed\n\x1b[31m│  ✗ Failed to install seo-audit\x1b[39m\n";
+        let lines = clean_output_lines(raw);
+        assert_eq!(
+            lines,
+            vec![
+                "Installing skills",
+                "frontend-design installed",
+                "✗ Failed to install seo-audit",
+            ]
+        );
+    }
+
+    // -------- install cancellation --------
+
+    #[cfg(unix)]
+    #[test]
+    fn connected_clients_are_not_port_holders() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut client = std::process::Command::new("bash")
+            .args(["-c", &format!("exec 3<>/dev/tcp/127.0.0.1/{}; sleep 30", port)])
+            .spawn()
+            .unwrap();
+        let _accepted = listener.accept().unwrap();
+
+        // Wait until lsof sees the client's end of the connection
+        let started = std::time::Instant::now();
+        loop {
+            let out = std::process::Command::new("lsof")
+                .args(["-ti", &format!("tcp:{}", port)])
+                .output()
+                .unwrap();
+            if parse_lsof_pids(&String::from_utf8_lossy(&out.stdout)).contains(&client.id()) {
+                break;
+            }
+            assert!(started.elapsed() < std::time::Duration::from_secs(5));
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        let holders = pids_on_port(port);
+        let _ = client.kill();
+        let _ = client.wait();
+        assert!(holders.contains(&std::process::id()));
+        assert!(!holders.contains(&client.id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_process_tree_reaches_grandchildren() {
+        // bash -> sh -> sleep, like bash -> install script -> curl
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sh -c 'sleep 30; echo' & wait"])
+            .spawn()
+            .unwrap();
+        let root = child.id();
+
+        let descendants = |pid: u32| -> Vec<u32> {
+            let out = std::process::Command::new("pgrep")
+                .args(["-P", &pid.to_string()])
+                .output()
+                .unwrap();
+            parse_lsof_pids(&String::from_utf8_lossy(&out.stdout))
+        };
+        let started = std::time::Instant::now();
+        let grandchild = loop {
+            let grandchildren: Vec<u32> =
+                descendants(root).into_iter().flat_map(descendants).collect();
+            if let Some(&grandchild) = grandchildren.first() {
+                break grandchild;
+            }
+            assert!(started.elapsed() < std::time::Duration::from_secs(5));
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+
+        kill_process_tree(root);
+        child.wait().unwrap();
+        // The orphaned grandchild may linger as a zombie until init reaps it
+        let running = |pid: u32| {
+            std::process::Command::new("ps")
+                .args(["-o", "stat=", "-p", &pid.to_string()])
+                .output()
+                .map(|o| {
+                    let stat = String::from_utf8_lossy(&o.stdout);
+                    !stat.trim().is_empty() && !stat.trim_start().starts_with('Z')
+                })
+                .unwrap_or(false)
+        };
+        let started = std::time::Instant::now();
+        while running(grandchild) && started.elapsed() < std::time::Duration::from_secs(5) {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(!running(grandchild));
+    }
+
+    // -------- port reuse --------
+
+    #[test]
+    fn restart_keeps_pinned_port_while_free() {
+        let free = |port: u16| port != 4096;
+        assert_eq!(first_free_port(&[Some(5000), Some(6000)], free), Some(5000));
+        // Pinned port taken: fall back to the tracked one
+        assert_eq!(first_free_port(&[Some(4096), Some(6000)], free), Some(6000));
+        // Nothing usable: caller picks and persists a fresh port
+        assert_eq!(first_free_port(&[Some(4096), None], free), None);
+        assert_eq!(first_free_port(&[None, None], free), None);
+    }
+
+    // -------- install script checksum --------
+
+    #[test]
+    fn unpinned_script_is_accepted() {
+        assert!(verify_sha256(b"echo hi", None).is_ok());
+    }
+
+    #[test]
+    fn checksum_mismatch_is_rejected() {
+        // sha256("echo hi\n")
+        let pinned = "ab08508fdf5ca4da5c4995987bc41c56c048aaa5eeb046417ae4049b7d40286e";
+        assert!(verify_sha256(b"echo hi\n", Some(pinned)).is_ok());
+        assert!(verify_sha256(b"echo pwned\n", Some(pinned)).is_err());
+    }
+
+    #[test]
+    fn error_lines_keep_only_failures() {
+        let lines = clean_output_lines("ok: a\nError: repo not found\nok: b\nfailed to clone\n");
+        assert_eq!(error_lines(&lines), vec!["Error: repo not found", "failed to clone"]);
+    }
+}
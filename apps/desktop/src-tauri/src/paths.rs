@@ -1,10 +1,68 @@
+use std::fs;
 use std::path::PathBuf;
 
-/// Root directory for all Dilag data (~/.dilag)
+/// Environment variable that overrides the data directory
+pub const DATA_DIR_ENV: &str = "DILAG_DATA_DIR";
+
+/// Default data directory (~/.dilag). Without a resolvable home directory
+/// (some containers and sandboxes) this falls back to a temp dir instead of
+/// panicking; `is_data_dir_fallback` reports when that happened.
+pub fn get_default_dilag_dir() -> PathBuf {
+    default_dilag_dir_for(dirs::home_dir())
+}
+
+fn default_dilag_dir_for(home: Option<PathBuf>) -> PathBuf {
+    match home {
+        Some(home) => home.join(".dilag"),
+        None => {
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            let fallback = std::env::temp_dir().join("dilag");
+            WARNED.call_once(|| {
+                println!(
+                    "[paths] Warning: no home directory, using {} for data",
+                    fallback.display()
+                )
+            });
+            fallback
+        }
+    }
+}
+
+/// Whether data lives in the temp fallback because no home dir was found
+pub fn is_data_dir_fallback() -> bool {
+    dirs::home_dir().is_none()
+        && std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()).is_none()
+        && read_bootstrap_data_dir().is_none()
+}
+
+/// File in the OS config dir holding a user-chosen data directory. It lives
+/// outside the data directory so it can be read before the root is known.
+pub fn get_data_dir_bootstrap_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("dilag").join("data-dir"))
+}
+
+fn read_bootstrap_data_dir() -> Option<PathBuf> {
+    let content = fs::read_to_string(get_data_dir_bootstrap_file()?).ok()?;
+    let path = content.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Root directory for all Dilag data: `DILAG_DATA_DIR`, then the bootstrap
+/// file written by `set_data_dir`, then ~/.dilag
 pub fn get_dilag_dir() -> PathBuf {
-    dirs::home_dir()
-        .expect("Could not find home directory")
-        .join(".dilag")
+    dilag_dir_for(std::env::var_os(DATA_DIR_ENV), read_bootstrap_data_dir)
+}
+
+/// `get_dilag_dir` with the env value passed in; an empty value is ignored.
+/// `bootstrap` is only consulted when the env var doesn't decide.
+fn dilag_dir_for(
+    env_value: Option<std::ffi::OsString>,
+    bootstrap: impl FnOnce() -> Option<PathBuf>,
+) -> PathBuf {
+    if let Some(dir) = env_value.filter(|v| !v.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    bootstrap().unwrap_or_else(get_default_dilag_dir)
 }
 
 /// Directory containing all session folders
@@ -21,3 +79,50 @@ pub fn get_sessions_file() -> PathBuf {
 pub fn get_opencode_config_dir() -> PathBuf {
     get_dilag_dir().join("opencode")
 }
+
+/// `XDG_DATA_HOME` for OpenCode when its data is isolated from the user's own
+/// install (sessions, auth, logs end up in `<data dir>/data/opencode`)
+pub fn get_opencode_data_home() -> PathBuf {
+    get_dilag_dir().join("data")
+}
+
+/// Advisory lock holding the PID of the Dilag instance that owns the data dir
+pub fn get_instance_lock_file() -> PathBuf {
+    get_dilag_dir().join("instance.lock")
+}
+
+/// Cross-session design search index (see `search.rs`)
+pub fn get_search_index_file() -> PathBuf {
+    get_dilag_dir().join("search-index.json")
+}
+
+/// JSON file storing user settings (permission overrides, preferences)
+pub fn get_settings_file() -> PathBuf {
+    get_dilag_dir().join("settings.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_home_falls_back_to_temp_dir() {
+        let home = PathBuf::from("/home/someone");
+        assert_eq!(default_dilag_dir_for(Some(home.clone())), home.join(".dilag"));
+        assert_eq!(default_dilag_dir_for(None), std::env::temp_dir().join("dilag"));
+    }
+
+    #[test]
+    fn env_var_overrides_data_dir() {
+        let env_dir = PathBuf::from("/data/from-env");
+        let chosen = PathBuf::from("/data/chosen");
+
+        let resolved = dilag_dir_for(Some(env_dir.clone().into()), || Some(chosen.clone()));
+        assert_eq!(resolved, env_dir);
+
+        // Unset or empty falls through to the bootstrap file, then the default
+        assert_eq!(dilag_dir_for(None, || Some(chosen.clone())), chosen);
+        assert_eq!(dilag_dir_for(Some("".into()), || Some(chosen.clone())), chosen);
+        assert_eq!(dilag_dir_for(None, || None), get_default_dilag_dir());
+    }
+}
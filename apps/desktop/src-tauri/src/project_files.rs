@@ -0,0 +1,520 @@
+//! List, read, write and rename access to files inside a session's project directory.
+//!
+//! Every path coming from the frontend is resolved through
+//! [`resolve_session_path`], which canonicalizes it and rejects anything
+//! that lands outside the session root.
+
+use crate::error::{AppError, AppResult};
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Refuse to ship files larger than this over IPC
+const MAX_READ_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Autosaves to the same file within this window are coalesced into one
+/// write of the latest content, so typing doesn't thrash disk or HMR
+const AUTOSAVE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Folders left out of the file tree: dependencies, VCS data and Dilag's caches
+const TREE_IGNORED_DIRS: &[&str] = &["node_modules", ".git", ".history", ".thumbnails"];
+
+/// Latest unsaved content per file, waiting for its debounce window to end
+static PENDING_SAVES: Mutex<Option<HashMap<PathBuf, String>>> = Mutex::new(None);
+
+#[derive(Debug, Serialize)]
+pub struct ReadResult {
+    /// Base64-encoded file contents
+    pub content: String,
+    pub mime_type: String,
+    pub size: u64,
+}
+
+/// Entry in the project file tree, shaped like the frontend's `FileNode`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNode {
+    /// Path relative to the session, `/`-separated
+    pub id: String,
+    pub name: String,
+    pub is_dir: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<FileNode>>,
+}
+
+/// Walk each component of `file_path` under `root` and reject symlinks whose
+/// target leaves the session, including dangling ones. Gives a specific error
+/// instead of whatever canonicalize happens to report.
+fn check_symlinks(root: &Path, file_path: &str) -> AppResult<()> {
+    let denied = || AppError::Custom("Access denied: symlink escapes session".to_string());
+    let mut current = root.to_path_buf();
+
+    for component in Path::new(file_path).components() {
+        match component {
+            Component::Normal(part) => current.push(part),
+            Component::ParentDir => {
+                current.pop();
+                continue;
+            }
+            Component::CurDir => continue,
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(AppError::Custom(
+                    "Access denied: absolute paths are not allowed".to_string(),
+                ));
+            }
+        }
+
+        let Ok(meta) = fs::symlink_metadata(&current) else {
+            // Doesn't exist; canonicalize will report it
+            return Ok(());
+        };
+        if !meta.file_type().is_symlink() {
+            continue;
+        }
+
+        match fs::canonicalize(&current) {
+            Ok(target) if target.starts_with(root) => {}
+            Ok(_) => return Err(denied()),
+            Err(_) => {
+                // Dangling link: judge the target lexically
+                let target = fs::read_link(&current)?;
+                let target = match current.parent() {
+                    Some(parent) if target.is_relative() => parent.join(target),
+                    _ => target,
+                };
+                if !target.starts_with(root) || target.components().any(|c| c == Component::ParentDir) {
+                    return Err(denied());
+                }
+                return Err(AppError::Custom(format!("Broken symlink: {}", file_path)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve `file_path` relative to the session and make sure the canonical
+/// result is still inside the session directory.
+pub(crate) fn resolve_session_path(session_cwd: &str, file_path: &str) -> AppResult<PathBuf> {
+    let root = fs::canonicalize(session_cwd)
+        .map_err(|e| AppError::ProjectNotReady(format!("Invalid session directory: {}", e)))?;
+    check_symlinks(&root, file_path)?;
+    let full = fs::canonicalize(root.join(file_path))
+        .map_err(|e| AppError::Custom(format!("Failed to resolve {}: {}", file_path, e)))?;
+
+    if !full.starts_with(&root) {
+        return Err(AppError::Custom(
+            "Access denied: path is outside the session".to_string(),
+        ));
+    }
+
+    Ok(full)
+}
+
+/// Guess a MIME type from the file extension
+fn mime_type_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "txt" | "md" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Read any project file (e.g. images) as base64 with a detected MIME type
+#[tauri::command]
+pub fn read_project_file_bytes(session_cwd: String, file_path: String) -> AppResult<ReadResult> {
+    let path = resolve_session_path(&session_cwd, &file_path)?;
+
+    let size = fs::metadata(&path)?.len();
+    if size > MAX_READ_BYTES {
+        return Err(AppError::Custom(format!(
+            "{} is too large to preview ({} bytes)",
+            file_path, size
+        )));
+    }
+
+    let bytes = fs::read(&path)?;
+    Ok(ReadResult {
+        content: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        mime_type: mime_type_for(&path).to_string(),
+        size,
+    })
+}
+
+/// Read a text project file (source, config) through the same path checks as
+/// `read_project_file_bytes`. Binary files are refused; use the bytes variant.
+#[tauri::command]
+pub fn read_project_file(session_cwd: String, file_path: String) -> AppResult<String> {
+    let path = resolve_session_path(&session_cwd, &file_path)?;
+
+    let size = fs::metadata(&path)?.len();
+    if size > MAX_READ_BYTES {
+        return Err(AppError::Custom(format!(
+            "{} is too large to preview ({} bytes)",
+            file_path, size
+        )));
+    }
+
+    String::from_utf8(fs::read(&path)?)
+        .map_err(|_| AppError::Custom(format!("{} is not a text file", file_path)))
+}
+
+/// Directory entries of `dir` as tree nodes, folders first. Symlinked folders
+/// are listed but not descended into, so the walk can't leave the session.
+fn list_tree(root: &Path, dir: &Path) -> Vec<FileNode> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<FileNode> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let file_type = entry.file_type().ok()?;
+            if file_type.is_dir() && TREE_IGNORED_DIRS.contains(&name.as_str()) {
+                return None;
+            }
+            let path = entry.path();
+            let id = path
+                .strip_prefix(root)
+                .ok()?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let is_dir = file_type.is_dir() || (file_type.is_symlink() && path.is_dir());
+            Some(FileNode {
+                id,
+                name,
+                is_dir,
+                children: file_type.is_dir().then(|| list_tree(root, &path)),
+            })
+        })
+        .collect();
+    nodes.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    nodes
+}
+
+/// File tree of the session's project, skipping dependencies and caches
+#[tauri::command]
+pub fn list_project_files(session_cwd: String) -> AppResult<Vec<FileNode>> {
+    let root = resolve_session_path(&session_cwd, ".")?;
+    Ok(list_tree(&root, &root))
+}
+
+/// Resolve a path that may not exist yet: its parent must resolve inside the
+/// session and only a plain file name is accepted on top. An existing symlink
+/// there is refused, since writing through it would follow the link.
+fn resolve_new_session_path(session_cwd: &str, file_path: &str) -> AppResult<PathBuf> {
+    let path = Path::new(file_path);
+    let name = match path.file_name() {
+        Some(name) if path.components().next_back() == Some(Component::Normal(name)) => name,
+        _ => return Err(AppError::Custom(format!("Invalid destination: {}", file_path))),
+    };
+    let parent = match path.parent().map(|p| p.to_string_lossy().to_string()) {
+        Some(p) if !p.is_empty() => p,
+        _ => ".".to_string(),
+    };
+    let dest = resolve_session_path(session_cwd, &parent)?.join(name);
+    if fs::symlink_metadata(&dest).is_ok_and(|m| m.file_type().is_symlink()) {
+        return Err(AppError::Custom(
+            "Access denied: symlink escapes session".to_string(),
+        ));
+    }
+    Ok(dest)
+}
+
+/// Resolve an autosave target. Only a file that doesn't exist yet falls back
+/// to `resolve_new_session_path`; any other resolve error (an escaping or
+/// dangling symlink) is returned as is.
+fn resolve_autosave_path(session_cwd: &str, file_path: &str) -> AppResult<PathBuf> {
+    match resolve_session_path(session_cwd, file_path) {
+        Ok(path) => Ok(path),
+        Err(_) if fs::symlink_metadata(Path::new(session_cwd).join(file_path)).is_err() => {
+            resolve_new_session_path(session_cwd, file_path)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Rename `from` to `to` (both relative to the session) without leaving the
+/// session or overwriting anything. Returns the new absolute path.
+fn rename_within(session_cwd: &str, from: &str, to: &str) -> AppResult<PathBuf> {
+    let source = resolve_session_path(session_cwd, from)?;
+    if fs::canonicalize(session_cwd).is_ok_and(|root| root == source) {
+        return Err(AppError::Custom("Cannot rename the session directory".to_string()));
+    }
+
+    let dest = resolve_new_session_path(session_cwd, to)?;
+
+    if fs::symlink_metadata(&dest).is_ok() {
+        return Err(AppError::Custom(format!("{} already exists", to)));
+    }
+    fs::rename(&source, &dest)?;
+    Ok(dest)
+}
+
+/// Rename a project file or folder inside the session. Imports aren't
+/// updated, so `project:file-renamed` is emitted for the frontend to warn
+/// about references to the old path.
+#[tauri::command]
+pub fn rename_project_path(
+    app: AppHandle,
+    session_cwd: String,
+    from: String,
+    to: String,
+) -> AppResult<String> {
+    let dest = rename_within(&session_cwd, &from, &to)?;
+    let _ = app.emit(
+        "project:file-renamed",
+        serde_json::json!({ "session_cwd": session_cwd, "from": from, "to": to }),
+    );
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Queue `content` for `path`. The first call in a quiet period starts the
+/// debounce timer; later calls only replace the content, and `on_write` runs
+/// once with the result of the single write.
+fn schedule_save(
+    path: PathBuf,
+    content: String,
+    on_write: impl FnOnce(&Path, std::io::Result<()>) + Send + 'static,
+) {
+    let first = PENDING_SAVES
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(path.clone(), content)
+        .is_none();
+    if !first {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(AUTOSAVE_DEBOUNCE);
+        let content = PENDING_SAVES
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|pending| pending.remove(&path));
+        if let Some(content) = content {
+            on_write(&path, fs::write(&path, content));
+        }
+    });
+}
+
+/// Save an editor buffer to a project file, creating it if needed. Writes
+/// to the same file are coalesced to one per `AUTOSAVE_DEBOUNCE`;
+/// `project:saved` is emitted after each actual write.
+#[tauri::command]
+pub fn autosave_project_file(
+    app: AppHandle,
+    session_cwd: String,
+    file_path: String,
+    content: String,
+) -> AppResult<()> {
+    let path = resolve_autosave_path(&session_cwd, &file_path)?;
+    if path.is_dir() {
+        return Err(AppError::Custom(format!("{} is a directory", file_path)));
+    }
+
+    schedule_save(path, content, move |path, result| match result {
+        Ok(()) => {
+            let _ = app.emit(
+                "project:saved",
+                serde_json::json!({ "session_cwd": session_cwd, "file_path": file_path }),
+            );
+        }
+        Err(e) => println!("[project_files] Autosave of {} failed: {}", path.display(), e),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rapid_autosaves_coalesce_into_one_write() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("App.tsx");
+        let writes = Arc::new(AtomicU32::new(0));
+        for n in 0..5 {
+            let writes = writes.clone();
+            schedule_save(path.clone(), format!("v{}", n), move |_, result| {
+                result.unwrap();
+                writes.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        std::thread::sleep(AUTOSAVE_DEBOUNCE * 3);
+        assert_eq!(writes.load(Ordering::SeqCst), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v4");
+    }
+
+    #[test]
+    fn reads_image_as_base64_with_mime() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("favicon.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let result = read_project_file_bytes(
+            dir.path().to_string_lossy().to_string(),
+            "favicon.png".to_string(),
+        )
+        .unwrap();
+        assert_eq!(result.mime_type, "image/png");
+        assert_eq!(result.content, "iVBORw==");
+        assert_eq!(result.size, 4);
+    }
+
+    #[test]
+    fn reads_text_and_refuses_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("App.tsx"), "export default 1;\n").unwrap();
+        fs::write(dir.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+
+        let text = read_project_file(cwd.clone(), "App.tsx".to_string()).unwrap();
+        assert_eq!(text, "export default 1;\n");
+        assert!(read_project_file(cwd.clone(), "logo.png".to_string()).is_err());
+        assert!(read_project_file(cwd, "../outside.txt".to_string()).is_err());
+    }
+
+    #[test]
+    fn file_tree_skips_dependencies_and_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("components")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/react")).unwrap();
+        fs::create_dir_all(dir.path().join("screens/.history")).unwrap();
+        fs::write(src.join("components/Button.tsx"), "").unwrap();
+        fs::write(src.join("App.tsx"), "").unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+
+        let tree = list_project_files(dir.path().to_string_lossy().to_string()).unwrap();
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["screens", "src", "package.json"]);
+        assert_eq!(tree[0].children.as_ref().unwrap().len(), 0);
+
+        let src_children = tree[1].children.as_ref().unwrap();
+        assert_eq!(src_children[0].id, "src/components");
+        assert!(src_children[0].is_dir);
+        assert_eq!(src_children[1].id, "src/App.tsx");
+        assert!(src_children[1].children.is_none());
+    }
+
+    #[test]
+    fn blocks_parent_directory_escape() {
+        let outer = tempfile::tempdir().unwrap();
+        let session = outer.path().join("session");
+        fs::create_dir_all(&session).unwrap();
+        fs::write(outer.path().join("secret.png"), b"nope").unwrap();
+
+        let result = read_project_file_bytes(
+            session.to_string_lossy().to_string(),
+            "../secret.png".to_string(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rename_moves_within_session_only() {
+        let outer = tempfile::tempdir().unwrap();
+        let session = outer.path().join("session");
+        fs::create_dir_all(session.join("src/components")).unwrap();
+        fs::write(session.join("src/foo.tsx"), "foo").unwrap();
+        fs::write(session.join("src/taken.tsx"), "taken").unwrap();
+        let cwd = session.to_string_lossy().to_string();
+
+        let dest = rename_within(&cwd, "src/foo.tsx", "src/components/bar.tsx").unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "foo");
+        assert!(!session.join("src/foo.tsx").exists());
+
+        // Never overwrite, never leave the session
+        assert!(rename_within(&cwd, "src/components/bar.tsx", "src/taken.tsx").is_err());
+        assert!(rename_within(&cwd, "src/taken.tsx", "../escaped.tsx").is_err());
+        assert!(rename_within(&cwd, "src/taken.tsx", "src/..").is_err());
+        assert!(rename_within(&cwd, ".", "moved").is_err());
+        assert!(!outer.path().join("escaped.tsx").exists());
+        assert_eq!(fs::read_to_string(session.join("src/taken.tsx")).unwrap(), "taken");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_escaping_session() {
+        let outer = tempfile::tempdir().unwrap();
+        let session = outer.path().join("session");
+        fs::create_dir_all(&session).unwrap();
+        fs::write(outer.path().join("secret.png"), b"nope").unwrap();
+        std::os::unix::fs::symlink(outer.path().join("secret.png"), session.join("logo.png")).unwrap();
+        std::os::unix::fs::symlink(outer.path().join("missing.png"), session.join("gone.png")).unwrap();
+
+        let cwd = session.to_string_lossy().to_string();
+        for name in ["logo.png", "gone.png"] {
+            let err = read_project_file_bytes(cwd.clone(), name.to_string()).unwrap_err();
+            assert_eq!(err.to_string(), "Access denied: symlink escapes session");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn autosave_does_not_write_through_escaping_symlink() {
+        let outer = tempfile::tempdir().unwrap();
+        let session = outer.path().join("session");
+        fs::create_dir_all(&session).unwrap();
+        fs::write(outer.path().join("secret.png"), b"nope").unwrap();
+        std::os::unix::fs::symlink(outer.path().join("secret.png"), session.join("logo.png")).unwrap();
+        std::os::unix::fs::symlink(outer.path().join("missing.png"), session.join("gone.png")).unwrap();
+
+        let cwd = session.to_string_lossy().to_string();
+        for name in ["logo.png", "gone.png"] {
+            let err = resolve_autosave_path(&cwd, name).unwrap_err();
+            assert_eq!(err.to_string(), "Access denied: symlink escapes session");
+            assert!(resolve_new_session_path(&cwd, name).is_err());
+        }
+        assert!(resolve_autosave_path(&cwd, "new.tsx").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allows_symlink_within_session() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("assets")).unwrap();
+        fs::write(dir.path().join("assets/logo.png"), b"png").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("assets"), dir.path().join("public")).unwrap();
+
+        let result = read_project_file_bytes(
+            dir.path().to_string_lossy().to_string(),
+            "public/logo.png".to_string(),
+        );
+        assert!(result.is_ok());
+    }
+}
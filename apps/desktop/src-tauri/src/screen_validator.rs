@@ -119,6 +119,116 @@ fn is_emoji(c: char) -> bool {
         || (0x2700..=0x27BF).contains(&cp) // Dingbats
 }
 
+/// Softer "AI slop" markers from the designer prompt. Unlike [`Rule`] these
+/// aren't hard errors, just hints that a screen looks generic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// Inter/Roboto/Arial and friends as the primary `--font-sans`
+    GenericFont,
+    /// Purple-to-blue Tailwind gradient
+    ClicheGradient,
+    /// The same card classes repeated across many elements
+    IdenticalCards,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DesignLint {
+    pub rule: LintRule,
+    pub message: String,
+    pub snippet: String,
+}
+
+/// Fonts that make a screen look like every other generated one
+const GENERIC_FONTS: &[&str] = &["inter", "roboto", "arial", "helvetica", "open sans"];
+
+/// How many elements may share the exact same card classes before it's flagged
+const IDENTICAL_CARD_LIMIT: usize = 4;
+
+struct LintCheck {
+    rule: LintRule,
+    message: &'static str,
+    /// Returns the offending snippet, if any
+    check: fn(&str) -> Option<String>,
+}
+
+/// Lint checks, run in order. Add new markers here.
+const LINT_CHECKS: &[LintCheck] = &[
+    LintCheck {
+        rule: LintRule::GenericFont,
+        message: "Primary font is a generic default; pick a typeface with more character",
+        check: lint_generic_font,
+    },
+    LintCheck {
+        rule: LintRule::ClicheGradient,
+        message: "Purple-to-blue gradient is a common AI-generated look",
+        check: lint_cliche_gradient,
+    },
+    LintCheck {
+        rule: LintRule::IdenticalCards,
+        message: "Many cards share identical styling; vary sizes to create hierarchy",
+        check: lint_identical_cards,
+    },
+];
+
+fn lint_generic_font(html: &str) -> Option<String> {
+    let re = regex::Regex::new(r#"--font-sans\s*:\s*["']?([^"',;]+)["']?[^;]*"#).expect("valid regex");
+    let caps = re.captures(html)?;
+    let primary = caps[1].trim().to_ascii_lowercase();
+    GENERIC_FONTS
+        .contains(&primary.as_str())
+        .then(|| caps[0].to_string())
+}
+
+fn lint_cliche_gradient(html: &str) -> Option<String> {
+    let re = regex::Regex::new(
+        r"\bfrom-(?:purple|violet|indigo|fuchsia)-\d+\b[^\x22]*?\bto-(?:blue|indigo|cyan|sky)-\d+\b|\bfrom-(?:blue|indigo|cyan|sky)-\d+\b[^\x22]*?\bto-(?:purple|violet|indigo|fuchsia)-\d+\b",
+    )
+    .expect("valid regex");
+    re.find(html).map(|m| m.as_str().to_string())
+}
+
+fn lint_identical_cards(html: &str) -> Option<String> {
+    let re = regex::Regex::new(r#"class\s*=\s*"([^"]*\brounded[^"]*)""#).expect("valid regex");
+    let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for caps in re.captures_iter(html) {
+        let classes = caps.get(1).expect("group 1").as_str();
+        // Only card-like boxes: padded and bordered or shadowed
+        let padded = classes.split_whitespace().any(|c| c.starts_with("p-"));
+        let framed = classes.contains("border") || classes.contains("shadow");
+        if padded && framed {
+            *counts.entry(classes).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(_, n)| *n >= IDENTICAL_CARD_LIMIT)
+        .max_by_key(|(_, n)| *n)
+        .map(|(classes, n)| format!("{}× class=\"{}\"", n, classes))
+}
+
+/// Run every lint rule over the given HTML
+pub fn lint(html: &str) -> Vec<DesignLint> {
+    LINT_CHECKS
+        .iter()
+        .filter_map(|lint| {
+            (lint.check)(html).map(|snippet| DesignLint {
+                rule: lint.rule,
+                message: lint.message.to_string(),
+                snippet,
+            })
+        })
+        .collect()
+}
+
+/// Lint a design file for generic-looking patterns
+#[tauri::command]
+pub fn lint_design(file_path: String) -> crate::error::AppResult<Vec<DesignLint>> {
+    let html = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    Ok(lint(&html))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,6 +413,34 @@ mod tests {
         assert_eq!(validate(html), vec![]);
     }
 
+    // -------- design lints --------
+
+    #[test]
+    fn lints_inter_as_primary_font() {
+        let html = r##"<style type="text/tailwindcss">@theme { --font-sans: "Inter", system-ui, sans-serif; }</style>"##;
+        let lints = lint(html);
+        assert_eq!(lints.len(), 1, "got {lints:?}");
+        assert_eq!(lints[0].rule, LintRule::GenericFont);
+        assert!(lints[0].snippet.contains("Inter"));
+    }
+
+    #[test]
+    fn allows_inter_as_fallback_font() {
+        let html = r##"@theme { --font-sans: "DM Sans", Inter, sans-serif; }"##;
+        assert!(!lint(html).iter().any(|l| l.rule == LintRule::GenericFont));
+    }
+
+    #[test]
+    fn lints_purple_blue_gradient_and_repeated_cards() {
+        let card = r#"<div class="rounded-xl border p-6"></div>"#;
+        let html = format!(
+            r#"<section class="bg-gradient-to-r from-purple-500 to-blue-500">{}</section>"#,
+            card.repeat(4)
+        );
+        let rules: Vec<_> = lint(&html).into_iter().map(|l| l.rule).collect();
+        assert_eq!(rules, vec![LintRule::ClicheGradient, LintRule::IdenticalCards]);
+    }
+
     // -------- our bundled exemplars themselves must pass --------
 
     #[test]
@@ -0,0 +1,354 @@
+//! Cross-session design search backed by an on-disk index.
+//!
+//! `search-index.json` maps each design file to its session, title and word
+//! tokens, so a query never walks the sessions dir. The session watcher keeps
+//! entries current as screens change; `rebuild_search_index` regenerates it
+//! from scratch. Entries whose file has disappeared are dropped on query.
+
+use crate::designs::{read_design_file, resolve_design_files};
+use crate::error::AppResult;
+use crate::paths::get_search_index_file;
+use crate::sessions::load_sessions_store;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Shortest word kept as a token
+const MIN_TOKEN_LEN: usize = 2;
+/// Upper bound on hits returned by `search_designs`
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// Serializes load-modify-save of the index file across the watcher threads
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    session_id: String,
+    session_cwd: String,
+    filename: String,
+    title: String,
+    modified_at: u64,
+    tokens: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchIndex {
+    /// Keyed by canonical file path
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_cwd: String,
+    pub path: String,
+    pub filename: String,
+    pub title: String,
+    pub modified_at: u64,
+}
+
+/// Lowercase words from a design's title, filename and visible text
+fn tokenize(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_TOKEN_LEN)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Text a user would see: tags, scripts and styles stripped
+fn visible_text(html: &str) -> String {
+    let blocks =
+        regex::Regex::new(r"(?is)<(script|style)[^>]*>.*?</(script|style)>").expect("valid regex");
+    let tags = regex::Regex::new(r"(?s)<[^>]*>").expect("valid regex");
+    tags.replace_all(&blocks.replace_all(html, " "), " ")
+        .into_owned()
+}
+
+fn index_key(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn build_entry(session_id: &str, session_cwd: &str, path: &Path) -> Option<IndexEntry> {
+    let design = read_design_file(path)?;
+    let text = format!(
+        "{} {} {}",
+        design.title,
+        design.filename,
+        visible_text(&design.html)
+    );
+    Some(IndexEntry {
+        session_id: session_id.to_string(),
+        session_cwd: session_cwd.to_string(),
+        filename: design.filename,
+        title: design.title,
+        modified_at: design.modified_at,
+        tokens: tokenize(&text),
+    })
+}
+
+fn load_index(index_file: &Path) -> Option<SearchIndex> {
+    serde_json::from_str(&fs::read_to_string(index_file).ok()?).ok()
+}
+
+fn save_index(index_file: &Path, index: &SearchIndex) -> AppResult<()> {
+    if let Some(parent) = index_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(index_file, serde_json::to_string(index)?)?;
+    Ok(())
+}
+
+/// Index every design of the given `(session id, cwd)` pairs
+fn build_index<'a>(sessions: impl IntoIterator<Item = (&'a str, &'a str)>) -> SearchIndex {
+    let mut index = SearchIndex::default();
+    for (session_id, session_cwd) in sessions {
+        for path in resolve_design_files(Path::new(session_cwd)) {
+            if let Some(entry) = build_entry(session_id, session_cwd, &path) {
+                index.entries.insert(index_key(&path), entry);
+            }
+        }
+    }
+    index
+}
+
+/// Entries matching every query word (as a prefix of one of their tokens),
+/// title matches first, then most recently modified
+fn query_index(index: &SearchIndex, query: &str) -> Vec<SearchHit> {
+    let words = tokenize(query);
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<(usize, SearchHit)> = index
+        .entries
+        .iter()
+        .filter(|(_, entry)| {
+            words.iter().all(|word| {
+                entry
+                    .tokens
+                    .range(word.clone()..)
+                    .next()
+                    .is_some_and(|t| t.starts_with(word.as_str()))
+            })
+        })
+        .map(|(path, entry)| {
+            let title_tokens = tokenize(&entry.title);
+            let title_matches = words
+                .iter()
+                .filter(|word| title_tokens.iter().any(|t| t.starts_with(word.as_str())))
+                .count();
+            (
+                title_matches,
+                SearchHit {
+                    session_id: entry.session_id.clone(),
+                    session_cwd: entry.session_cwd.clone(),
+                    path: path.clone(),
+                    filename: entry.filename.clone(),
+                    title: entry.title.clone(),
+                    modified_at: entry.modified_at,
+                },
+            )
+        })
+        .collect();
+
+    hits.sort_by(|(a_score, a), (b_score, b)| {
+        b_score.cmp(a_score).then(b.modified_at.cmp(&a.modified_at))
+    });
+    hits.into_iter()
+        .map(|(_, hit)| hit)
+        .take(MAX_SEARCH_RESULTS)
+        .collect()
+}
+
+/// Drop entries whose file no longer exists. Returns whether any were removed.
+fn prune_stale(index: &mut SearchIndex) -> bool {
+    let before = index.entries.len();
+    index.entries.retain(|path, _| Path::new(path).is_file());
+    index.entries.len() != before
+}
+
+fn rebuild_index_file(index_file: &Path) -> AppResult<SearchIndex> {
+    let store = load_sessions_store();
+    let index = build_index(
+        store
+            .sessions
+            .iter()
+            .map(|s| (s.id.as_str(), s.cwd.as_str())),
+    );
+    save_index(index_file, &index)?;
+    Ok(index)
+}
+
+/// Refresh `paths` (designs of one session) in `index`: existing files are
+/// re-read, missing ones dropped. Returns whether anything changed.
+fn apply_changes(
+    index: &mut SearchIndex,
+    session_id: Option<&str>,
+    session_cwd: &str,
+    paths: &[PathBuf],
+) -> bool {
+    let mut changed = false;
+    for path in paths {
+        if path.exists() {
+            let Some(session_id) = session_id else {
+                continue;
+            };
+            match build_entry(session_id, session_cwd, path) {
+                Some(entry) => index.entries.insert(index_key(path), entry),
+                None => index.entries.remove(&index_key(path)),
+            };
+            changed = true;
+        } else {
+            // The file is gone, so it can't be canonicalized; match on the raw path too
+            let raw = path.to_string_lossy().to_string();
+            changed |= index.entries.remove(&raw).is_some();
+        }
+    }
+    changed | prune_stale(index)
+}
+
+/// Apply a batch of design changes from one session to the index with a
+/// single read and write; called by the session watcher once per debounced
+/// batch. Does nothing until the index has been built once.
+pub(crate) fn update_index(session_cwd: &str, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let index_file = get_search_index_file();
+    let Some(mut index) = load_index(&index_file) else {
+        return;
+    };
+    let session_id = load_sessions_store()
+        .sessions
+        .into_iter()
+        .find(|s| s.cwd == session_cwd || index_key(Path::new(&s.cwd)) == session_cwd)
+        .map(|s| s.id);
+
+    if apply_changes(&mut index, session_id.as_deref(), session_cwd, paths) {
+        if let Err(e) = save_index(&index_file, &index) {
+            println!("[search] Failed to update index: {}", e);
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Regenerate the search index from every session on disk. Returns the
+/// number of designs indexed.
+#[tauri::command]
+pub fn rebuild_search_index() -> AppResult<u32> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let index = rebuild_index_file(&get_search_index_file())?;
+    Ok(index.entries.len() as u32)
+}
+
+/// Find designs across all sessions whose title, filename or text contains
+/// every word of `query`. Builds the index on first use.
+#[tauri::command]
+pub fn search_designs(query: String) -> AppResult<Vec<SearchHit>> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let index_file = get_search_index_file();
+    let mut index = match load_index(&index_file) {
+        Some(index) => index,
+        None => rebuild_index_file(&index_file)?,
+    };
+    if prune_stale(&mut index) {
+        save_index(&index_file, &index)?;
+    }
+    Ok(query_index(&index, &query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(title: &str, body: &str) -> String {
+        format!(
+            r#"<!DOCTYPE html><html data-title="{}"><head><style>.checkout {{}}</style></head><body>{}</body></html>"#,
+            title, body
+        )
+    }
+
+    #[test]
+    fn batched_changes_refresh_and_drop_entries() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        let cart = screens.join("cart.html");
+        let home = screens.join("home.html");
+        fs::write(&cart, screen("Cart", "<h1>Your basket</h1>")).unwrap();
+        fs::write(&home, screen("Home", "")).unwrap();
+
+        let cwd = session.path().to_string_lossy().to_string();
+        let mut index = build_index([("s1", cwd.as_str())]);
+        let raw_home = home.to_string_lossy().to_string();
+
+        fs::write(&cart, screen("Checkout", "")).unwrap();
+        fs::remove_file(&home).unwrap();
+        let changed = apply_changes(&mut index, Some("s1"), &cwd, &[cart, home]);
+        assert!(changed);
+        assert_eq!(index.entries.len(), 1);
+        assert!(!index.entries.contains_key(&raw_home));
+        assert_eq!(query_index(&index, "checkout").len(), 1);
+        assert!(query_index(&index, "basket").is_empty());
+
+        assert!(!apply_changes(&mut index, Some("s1"), &cwd, &[]));
+    }
+
+    #[test]
+    fn index_matches_prefixes_of_visible_text() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(
+            screens.join("cart.html"),
+            screen("Cart", "<h1>Your basket</h1>"),
+        )
+        .unwrap();
+        fs::write(
+            screens.join("home.html"),
+            screen("Home", "<p>Cart preview</p>"),
+        )
+        .unwrap();
+
+        let cwd = session.path().to_string_lossy().to_string();
+        let index = build_index([("s1", cwd.as_str())]);
+        assert_eq!(index.entries.len(), 2);
+
+        let titles = |query: &str| -> Vec<String> {
+            query_index(&index, query)
+                .into_iter()
+                .map(|h| h.title)
+                .collect()
+        };
+        // Title matches rank first
+        assert_eq!(titles("car"), vec!["Cart", "Home"]);
+        assert_eq!(titles("your bask"), vec!["Cart"]);
+        // Style contents aren't indexed
+        assert!(titles("checkout").is_empty());
+    }
+
+    #[test]
+    fn stale_entries_are_pruned() {
+        let session = tempfile::tempdir().unwrap();
+        let screens = session.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(screens.join("cart.html"), screen("Cart", "")).unwrap();
+
+        let cwd = session.path().to_string_lossy().to_string();
+        let mut index = build_index([("s1", cwd.as_str())]);
+        assert!(!prune_stale(&mut index));
+
+        fs::remove_file(screens.join("cart.html")).unwrap();
+        assert!(prune_stale(&mut index));
+        assert!(query_index(&index, "cart").is_empty());
+    }
+}
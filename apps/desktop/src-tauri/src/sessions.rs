@@ -1,17 +1,159 @@
 use crate::error::AppResult;
 use crate::paths::{get_sessions_dir, get_sessions_file};
+use crate::settings::{load_settings, save_settings};
 use crate::state::{SessionMeta, SessionsStore};
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Load the sessions store from disk
-fn load_sessions_store() -> SessionsStore {
+/// Metadata file embedded at the root of an exported session archive
+const SESSION_ARCHIVE_META: &str = "dilag-session.json";
+
+/// Maximum number of entry paths returned by `inspect_session_zip`
+const MAX_MANIFEST_ENTRIES: usize = 200;
+
+/// Preview of a session archive, produced without extracting anything
+#[derive(Debug, Serialize)]
+pub struct ZipManifest {
+    /// Session name from the embedded metadata, if present
+    pub session_name: Option<String>,
+    pub file_count: usize,
+    pub total_uncompressed_size: u64,
+    /// Entry paths, capped at `MAX_MANIFEST_ENTRIES`
+    pub entries: Vec<String>,
+    pub truncated: bool,
+    /// Entries whose paths would escape the extraction directory
+    pub unsafe_entries: Vec<String>,
+}
+
+/// Current `sessions.json` schema. Bump it alongside a new step in `migrate_sessions_store`.
+pub(crate) const SESSIONS_SCHEMA_VERSION: u32 = 2;
+
+/// Upgrade a store read from disk to `SESSIONS_SCHEMA_VERSION`.
+/// Returns true if it changed and should be written back.
+fn migrate_sessions_store(store: &mut SessionsStore) -> bool {
+    if store.schema_version >= SESSIONS_SCHEMA_VERSION {
+        return false;
+    }
+
+    // v0 -> v1: `platform` and `favorite` are filled in by serde defaults on
+    // load; rewriting the store persists them explicitly.
+
+    // v1 -> v2: sessions from the older flat layout get an explicit platform
+    // and the `screens/` dir that `create_session_dir` now makes up front.
+    // Linked imports are left alone so we never write into the user's project.
+    if store.schema_version < 2 {
+        for session in &mut store.sessions {
+            session.platform.get_or_insert_with(|| "web".to_string());
+
+            let cwd = Path::new(&session.cwd);
+            let is_session_dir = fs::symlink_metadata(cwd).is_ok_and(|m| m.is_dir());
+            if is_session_dir {
+                if let Err(e) = fs::create_dir_all(cwd.join("screens")) {
+                    println!("[sessions] Failed to create screens/ for {}: {}", session.id, e);
+                }
+            }
+        }
+    }
+
+    store.schema_version = SESSIONS_SCHEMA_VERSION;
+    true
+}
+
+fn load_sessions_store_from(file_path: &Path) -> SessionsStore {
+    if !file_path.exists() {
+        return SessionsStore::default();
+    }
+
+    let content = fs::read_to_string(file_path).unwrap_or_default();
+    let mut store: SessionsStore = serde_json::from_str(&content).unwrap_or_default();
+    if migrate_sessions_store(&mut store) {
+        if let Ok(json) = serde_json::to_string_pretty(&store) {
+            let _ = fs::write(file_path, json);
+        }
+    }
+    store
+}
+
+/// Directories skipped when copying an existing project into a session
+const IMPORT_IGNORED_DIRS: &[&str] = &["node_modules", ".git"];
+
+/// Recursively copy a project, skipping ignored directories and symlinks
+/// (which could point outside the session once copied)
+fn copy_project_dir(source: &Path, dest: &Path) -> AppResult<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(source)?.flatten() {
+        let file_type = entry.file_type()?;
+        let name = entry.file_name();
+        let target = dest.join(&name);
+        if file_type.is_dir() {
+            if IMPORT_IGNORED_DIRS.iter().any(|d| name == *d) {
+                continue;
+            }
+            copy_project_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy or link `source` to `dest` after checking it looks like a web project
+fn import_project_into(source: &Path, dest: &Path, link: bool) -> AppResult<()> {
+    if !source.is_dir() {
+        return Err(format!("Not a directory: {}", source.display()).into());
+    }
+    if !source.join("package.json").is_file() {
+        return Err(format!("No package.json found in {}", source.display()).into());
+    }
+    if dest.exists() {
+        return Err(format!("Session directory already exists: {}", dest.display()).into());
+    }
+
+    if link {
+        #[cfg(unix)]
+        {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::os::unix::fs::symlink(fs::canonicalize(source)?, dest)?;
+        }
+        #[cfg(not(unix))]
+        return Err("Linking a project is only supported on macOS and Linux".into());
+    } else if let Err(e) = copy_project_dir(source, dest) {
+        let _ = fs::remove_dir_all(dest);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Load the sessions store from disk, migrating older formats in place
+pub(crate) fn load_sessions_store() -> SessionsStore {
+    load_sessions_store_from(&get_sessions_file())
+}
+
+/// Write the sessions store back to `sessions.json`
+pub(crate) fn save_sessions_store(store: &SessionsStore) -> AppResult<()> {
     let file_path = get_sessions_file();
-    if file_path.exists() {
-        let content = fs::read_to_string(&file_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        SessionsStore::default()
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    fs::write(&file_path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+/// Bring `sessions.json` and session dirs written by older builds up to the
+/// current layout. Loading the store already migrates it; doing so at startup
+/// means the dirs are fixed before anything scans them. A no-op once current.
+pub fn migrate_legacy_layout() {
+    let store = load_sessions_store();
+    println!(
+        "[setup] Sessions store at schema v{} ({} sessions)",
+        store.schema_version,
+        store.sessions.len()
+    );
 }
 
 // =============================================================================
@@ -29,6 +171,78 @@ pub fn create_session_dir(session_id: String) -> AppResult<String> {
     Ok(session_dir.to_string_lossy().to_string())
 }
 
+/// Session ids become directory names, so reject anything that could escape
+/// the sessions dir
+fn validate_session_id(session_id: &str) -> AppResult<()> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err(format!("Invalid session id: {}", session_id).into());
+    }
+    Ok(())
+}
+
+/// Format a time as an RFC 3339 UTC timestamp, matching the `toISOString()`
+/// values the frontend writes to `created_at`
+fn iso_timestamp(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = elapsed.as_secs();
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+/// Bring an existing web project (with a `package.json`) into a new session,
+/// copying it without `node_modules`/`.git` or, with `link`, symlinking it in
+/// place. The session is registered in `sessions.json` with `platform: "web"`;
+/// the frontend then creates the OpenCode session and records it with
+/// `set_opencode_session_id`.
+#[tauri::command]
+pub fn import_web_project(source_dir: String, name: String, link: bool) -> AppResult<SessionMeta> {
+    let now = SystemTime::now();
+    let nanos = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let session_id = format!("import-{:x}", nanos);
+    let session_dir = get_sessions_dir().join(&session_id);
+    import_project_into(Path::new(&source_dir), &session_dir, link)?;
+
+    let session = SessionMeta {
+        id: session_id,
+        name,
+        created_at: iso_timestamp(now),
+        cwd: session_dir.to_string_lossy().to_string(),
+        platform: Some("web".to_string()),
+        favorite: false,
+        opencode_session_id: None,
+    };
+    if let Err(e) = save_session_metadata(session.clone()) {
+        let _ = if link {
+            fs::remove_file(&session_dir)
+        } else {
+            fs::remove_dir_all(&session_dir)
+        };
+        return Err(e);
+    }
+    Ok(session)
+}
+
 #[tauri::command]
 pub fn get_session_cwd(session_id: String) -> String {
     get_sessions_dir()
@@ -37,67 +251,719 @@ pub fn get_session_cwd(session_id: String) -> String {
         .to_string()
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPaths {
+    pub root: String,
+    pub screens: Option<String>,
+    pub web_project: Option<String>,
+}
+
+fn session_paths_in(session_dir: &Path) -> SessionPaths {
+    let existing = |p: PathBuf| p.exists().then(|| p.to_string_lossy().to_string());
+    SessionPaths {
+        root: session_dir.to_string_lossy().to_string(),
+        screens: existing(session_dir.join("screens")),
+        web_project: session_dir
+            .join("package.json")
+            .is_file()
+            .then(|| session_dir.to_string_lossy().to_string()),
+    }
+}
+
+/// Absolute paths into a session for external tools (git, editors). Subpaths
+/// that don't exist are `None`.
 #[tauri::command]
-pub fn save_session_metadata(session: SessionMeta) -> AppResult<()> {
-    let file_path = get_sessions_file();
+pub fn get_session_paths(session_id: String) -> AppResult<SessionPaths> {
+    validate_session_id(&session_id)?;
+    let session_dir = get_sessions_dir().join(&session_id);
+    if !session_dir.is_dir() {
+        return Err(crate::error::AppError::SessionNotFound(session_id));
+    }
+    Ok(session_paths_in(&session_dir))
+}
 
-    if let Some(parent) = file_path.parent() {
-        fs::create_dir_all(parent)?;
+/// What a session holds, which decides between the gallery and a dev-server preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionMode {
+    /// Has a `package.json`; designs alongside it don't change that
+    WebProject,
+    /// HTML screens in `screens/` or the session root, no web project
+    ScreensOnly,
+    /// Nothing generated yet
+    Empty,
+}
+
+fn session_mode_in(session_dir: &Path) -> SessionMode {
+    if session_dir.join("package.json").is_file() {
+        SessionMode::WebProject
+    } else if !crate::designs::resolve_design_files(session_dir).is_empty() {
+        SessionMode::ScreensOnly
+    } else {
+        SessionMode::Empty
+    }
+}
+
+#[tauri::command]
+pub fn get_session_mode(session_cwd: String) -> SessionMode {
+    session_mode_in(Path::new(&session_cwd))
+}
+
+/// Per-session caches users can clear: `(name, folder)`. History and
+/// thumbnails sit next to the designs they belong to; `node_modules` only
+/// in the web project root.
+const SESSION_CACHES: &[(&str, &str)] = &[
+    ("history", ".history"),
+    ("thumbnails", ".thumbnails"),
+    ("node_modules", "node_modules"),
+];
+
+#[derive(Debug, Default, Serialize)]
+pub struct CacheSizes {
+    pub history_bytes: u64,
+    pub thumbnails_bytes: u64,
+    pub node_modules_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClearedCaches {
+    pub freed_bytes: u64,
+    pub warnings: Vec<String>,
+}
+
+/// Every folder of the named cache that exists in the session
+fn cache_dirs(session_dir: &Path, folder: &str) -> Vec<PathBuf> {
+    let candidates = if folder == "node_modules" {
+        vec![session_dir.join(folder)]
+    } else {
+        crate::designs::design_dirs(session_dir)
+            .into_iter()
+            .map(|dir| dir.join(folder))
+            .collect()
+    };
+    candidates
+        .into_iter()
+        .filter(|dir| fs::symlink_metadata(dir).is_ok_and(|m| m.is_dir()))
+        .collect()
+}
+
+fn cache_size(session_dir: &Path, folder: &str) -> u64 {
+    cache_dirs(session_dir, folder)
+        .iter()
+        .map(|dir| crate::app_info::calculate_dir_size(dir))
+        .sum()
+}
+
+/// Bytes used by a session's version history, thumbnail cache and `node_modules`
+#[tauri::command]
+pub fn get_session_cache_sizes(session_cwd: String) -> CacheSizes {
+    let session_dir = Path::new(&session_cwd);
+    CacheSizes {
+        history_bytes: cache_size(session_dir, ".history"),
+        thumbnails_bytes: cache_size(session_dir, ".thumbnails"),
+        node_modules_bytes: cache_size(session_dir, "node_modules"),
+    }
+}
+
+/// Delete the named caches (`history`, `thumbnails`, `node_modules`).
+/// Clearing history drops version history for good, so that comes back as a
+/// warning; `node_modules` needs a reinstall before the dev server runs again.
+#[tauri::command]
+pub fn clear_session_caches(session_cwd: String, which: Vec<String>) -> AppResult<ClearedCaches> {
+    let folders: Vec<&str> = which
+        .iter()
+        .map(|name| {
+            SESSION_CACHES
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, folder)| *folder)
+                .ok_or_else(|| crate::error::AppError::Custom(format!("Unknown cache: {}", name)))
+        })
+        .collect::<AppResult<_>>()?;
+
+    let session_dir = Path::new(&session_cwd);
+    let mut cleared = ClearedCaches {
+        freed_bytes: 0,
+        warnings: Vec::new(),
+    };
+    for folder in folders {
+        let dirs = cache_dirs(session_dir, folder);
+        if dirs.is_empty() {
+            continue;
+        }
+        for dir in dirs {
+            cleared.freed_bytes += crate::app_info::calculate_dir_size(&dir);
+            fs::remove_dir_all(&dir)?;
+        }
+        match folder {
+            ".history" => cleared
+                .warnings
+                .push("Version history was deleted; earlier versions of screens can't be restored".to_string()),
+            "node_modules" => cleared
+                .warnings
+                .push("Dependencies were removed; reinstall them before starting the dev server".to_string()),
+            _ => {}
+        }
     }
+    Ok(cleared)
+}
 
+#[tauri::command]
+pub fn save_session_metadata(session: SessionMeta) -> AppResult<()> {
     let mut store = load_sessions_store();
 
     if let Some(existing) = store.sessions.iter_mut().find(|s| s.id == session.id) {
-        *existing = session;
+        // Callers that don't know about the OpenCode mapping shouldn't erase it
+        let opencode_session_id = session
+            .opencode_session_id
+            .clone()
+            .or(existing.opencode_session_id.take());
+        *existing = SessionMeta {
+            opencode_session_id,
+            ..session
+        };
     } else {
         store.sessions.push(session);
     }
 
-    let json = serde_json::to_string_pretty(&store)?;
-    fs::write(&file_path, json)?;
+    save_sessions_store(&store)?;
 
     Ok(())
 }
 
+/// Metadata for one session, including the OpenCode session to restore its chat from
+#[tauri::command]
+pub fn get_session(session_id: String) -> AppResult<SessionMeta> {
+    load_sessions_store()
+        .sessions
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or(crate::error::AppError::SessionNotFound(session_id))
+}
+
+/// Values accepted for `SessionMeta::platform`
+const SESSION_PLATFORMS: &[&str] = &["web", "mobile"];
+
+fn validate_platform(platform: &str) -> AppResult<()> {
+    if SESSION_PLATFORMS.contains(&platform) {
+        Ok(())
+    } else {
+        Err(format!("Unknown platform \"{}\" (expected web or mobile)", platform).into())
+    }
+}
+
+/// Switch a session between web and mobile. Existing screens are left as
+/// they are; `session:platform-changed` lets the UI re-apply capture sizes
+/// and the design skill hint for new screens.
+#[tauri::command]
+pub fn set_session_platform(
+    app: tauri::AppHandle,
+    session_id: String,
+    platform: String,
+) -> AppResult<()> {
+    use tauri::Emitter;
+
+    validate_platform(&platform)?;
+    let mut store = load_sessions_store();
+    let session = store
+        .sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| crate::error::AppError::SessionNotFound(session_id.clone()))?;
+    session.platform = Some(platform.clone());
+
+    save_sessions_store(&store)?;
+
+    let _ = app.emit(
+        "session:platform-changed",
+        serde_json::json!({ "session_id": session_id, "platform": platform }),
+    );
+    Ok(())
+}
+
+/// Record which OpenCode session holds a Dilag session's chat thread
+#[tauri::command]
+pub fn set_opencode_session_id(session_id: String, opencode_id: String) -> AppResult<()> {
+    let mut store = load_sessions_store();
+    let session = store
+        .sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| crate::error::AppError::SessionNotFound(session_id.clone()))?;
+    session.opencode_session_id = Some(opencode_id);
+
+    save_sessions_store(&store)?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn load_sessions_metadata() -> Vec<SessionMeta> {
     load_sessions_store().sessions
 }
 
+/// Favorites first (by name), then the rest newest first. `created_at` is an
+/// ISO-8601 timestamp, so string order is chronological.
+fn sort_sessions(sessions: &mut [SessionMeta]) {
+    sessions.sort_by(|a, b| match (a.favorite, b.favorite) {
+        (true, true) => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        (false, false) => b.created_at.cmp(&a.created_at),
+    });
+}
+
+/// Sessions in sidebar order; `load_sessions_metadata` keeps insertion order
 #[tauri::command]
-pub fn delete_session_metadata(session_id: String) -> AppResult<()> {
-    let file_path = get_sessions_file();
+pub fn load_sessions_metadata_sorted() -> Vec<SessionMeta> {
+    let mut sessions = load_sessions_store().sessions;
+    sort_sessions(&mut sessions);
+    sessions
+}
+
+/// Server-side session filter; every field is optional and an empty filter
+/// matches everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SessionFilter {
+    /// `"web"` or `"mobile"`; sessions without a platform count as web
+    pub platform: Option<String>,
+    pub favorite_only: bool,
+    /// Case-insensitive substring of the session name
+    pub name: Option<String>,
+}
+
+impl SessionFilter {
+    fn matches(&self, session: &SessionMeta) -> bool {
+        if let Some(platform) = &self.platform {
+            if session.platform.as_deref().unwrap_or("web") != platform {
+                return false;
+            }
+        }
+        if self.favorite_only && !session.favorite {
+            return false;
+        }
+        if let Some(name) = self.name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            if !session.name.to_lowercase().contains(&name.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Sessions matching `filter`, in insertion order
+#[tauri::command]
+pub fn query_sessions(filter: SessionFilter) -> Vec<SessionMeta> {
+    load_sessions_store()
+        .sessions
+        .into_iter()
+        .filter(|s| filter.matches(s))
+        .collect()
+}
+
+#[tauri::command]
+pub fn delete_session_metadata(
+    state: tauri::State<'_, crate::state::AppState>,
+    session_id: String,
+) -> AppResult<()> {
+    state.ensure_not_busy()?;
+
     let mut store = load_sessions_store();
 
     store.sessions.retain(|s| s.id != session_id);
 
-    let json = serde_json::to_string_pretty(&store)?;
-    fs::write(&file_path, json)?;
+    save_sessions_store(&store)?;
 
     let session_dir = get_sessions_dir().join(&session_id);
     if session_dir.exists() {
         fs::remove_dir_all(&session_dir)?;
     }
 
+    let mut settings = load_settings();
+    if settings.last_active_session.as_deref() == Some(session_id.as_str()) {
+        settings.last_active_session = None;
+        save_settings(&settings)?;
+    }
+
     Ok(())
 }
 
+/// Remember the open session so the next launch can return to it
+#[tauri::command]
+pub fn set_last_active_session(session_id: String) -> AppResult<()> {
+    let mut settings = load_settings();
+    if settings.last_active_session.as_deref() == Some(session_id.as_str()) {
+        return Ok(());
+    }
+    settings.last_active_session = Some(session_id);
+    save_settings(&settings)
+}
+
+/// The last opened session, or `None` if it has since been deleted
+#[tauri::command]
+pub fn get_last_active_session() -> Option<String> {
+    let session_id = load_settings().last_active_session?;
+    load_sessions_store()
+        .sessions
+        .iter()
+        .any(|s| s.id == session_id)
+        .then_some(session_id)
+}
+
 #[tauri::command]
 pub fn toggle_session_favorite(session_id: String) -> AppResult<bool> {
-    let file_path = get_sessions_file();
     let mut store = load_sessions_store();
 
     let session = store
         .sessions
         .iter_mut()
         .find(|s| s.id == session_id)
-        .ok_or_else(|| crate::error::AppError::Custom(format!("Session {} not found", session_id)))?;
+        .ok_or_else(|| crate::error::AppError::SessionNotFound(session_id.clone()))?;
 
     session.favorite = !session.favorite;
     let new_favorite = session.favorite;
 
-    let json = serde_json::to_string_pretty(&store)?;
-    fs::write(&file_path, json)?;
+    save_sessions_store(&store)?;
 
     Ok(new_favorite)
 }
+
+/// List the contents of a session archive so the UI can preview an import
+#[tauri::command]
+pub fn inspect_session_zip(zip_path: String) -> AppResult<ZipManifest> {
+    let file = fs::File::open(&zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+
+    let mut manifest = ZipManifest {
+        session_name: None,
+        file_count: 0,
+        total_uncompressed_size: 0,
+        entries: Vec::new(),
+        truncated: false,
+        unsafe_entries: Vec::new(),
+    };
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let name = entry.name().to_string();
+
+        // enclosed_name rejects absolute paths and `..` components
+        if entry.enclosed_name().is_none() {
+            manifest.unsafe_entries.push(name.clone());
+        }
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        manifest.file_count += 1;
+        manifest.total_uncompressed_size += entry.size();
+
+        if name == SESSION_ARCHIVE_META {
+            let mut content = String::new();
+            if entry.read_to_string(&mut content).is_ok() {
+                manifest.session_name = serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(String::from));
+            }
+        }
+
+        if manifest.entries.len() < MAX_MANIFEST_ENTRIES {
+            manifest.entries.push(name);
+        } else {
+            manifest.truncated = true;
+        }
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn write_zip(path: &std::path::Path, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in files {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn meta(id: &str, name: &str, created_at: &str, favorite: bool) -> SessionMeta {
+        SessionMeta {
+            id: id.to_string(),
+            name: name.to_string(),
+            created_at: created_at.to_string(),
+            cwd: String::new(),
+            platform: None,
+            favorite,
+            opencode_session_id: None,
+        }
+    }
+
+    #[test]
+    fn session_filter_dimensions() {
+        let mut mobile = meta("m", "Coffee App", "2024-01-01T00:00:00.000Z", true);
+        mobile.platform = Some("mobile".to_string());
+        let web = meta("w", "Landing page", "2024-01-02T00:00:00.000Z", false);
+        let ids = |filter: SessionFilter| -> Vec<String> {
+            [&mobile, &web]
+                .into_iter()
+                .filter(|s| filter.matches(s))
+                .map(|s| s.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids(SessionFilter::default()), vec!["m", "w"]);
+        let platform = |p: &str| SessionFilter {
+            platform: Some(p.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(platform("web")), vec!["w"]);
+        assert_eq!(ids(platform("mobile")), vec!["m"]);
+        assert_eq!(
+            ids(SessionFilter {
+                favorite_only: true,
+                ..Default::default()
+            }),
+            vec!["m"]
+        );
+        assert_eq!(
+            ids(SessionFilter {
+                name: Some("coffee".to_string()),
+                ..Default::default()
+            }),
+            vec!["m"]
+        );
+        assert!(ids(SessionFilter {
+            platform: Some("web".to_string()),
+            favorite_only: true,
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            ids(SessionFilter {
+                platform: Some("mobile".to_string()),
+                favorite_only: true,
+                name: Some("APP".to_string()),
+            }),
+            vec!["m"]
+        );
+    }
+
+    #[test]
+    fn sort_puts_favorites_first_by_name_then_newest() {
+        let mut sessions = vec![
+            meta("a", "Old", "2024-01-01T00:00:00.000Z", false),
+            meta("b", "zeta", "2024-01-02T00:00:00.000Z", true),
+            meta("c", "New", "2024-03-01T00:00:00.000Z", false),
+            meta("d", "Alpha", "2023-06-01T00:00:00.000Z", true),
+            meta("e", "Mid", "2024-02-01T00:00:00.000Z", false),
+        ];
+        sort_sessions(&mut sessions);
+        let ids: Vec<_> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["d", "b", "c", "e", "a"]);
+    }
+
+    #[test]
+    fn import_copies_project_without_ignored_dirs() {
+        let source = tempfile::tempdir().unwrap();
+        fs::write(source.path().join("package.json"), "{}").unwrap();
+        fs::create_dir_all(source.path().join("src")).unwrap();
+        fs::write(source.path().join("src/main.tsx"), "app").unwrap();
+        fs::create_dir_all(source.path().join("node_modules/react")).unwrap();
+        fs::create_dir_all(source.path().join(".git")).unwrap();
+
+        let sessions = tempfile::tempdir().unwrap();
+        let dest = sessions.path().join("imported");
+        import_project_into(source.path(), &dest, false).unwrap();
+
+        assert!(dest.join("package.json").is_file());
+        assert_eq!(fs::read_to_string(dest.join("src/main.tsx")).unwrap(), "app");
+        assert!(!dest.join("node_modules").exists());
+        assert!(!dest.join(".git").exists());
+    }
+
+    #[test]
+    fn session_paths_only_report_existing_subpaths() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = session_paths_in(dir.path());
+        assert_eq!(paths.screens, None);
+        assert_eq!(paths.web_project, None);
+
+        fs::create_dir_all(dir.path().join("screens")).unwrap();
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let paths = session_paths_in(dir.path());
+        assert_eq!(
+            paths.screens.as_deref(),
+            Some(dir.path().join("screens").to_string_lossy().as_ref())
+        );
+        assert_eq!(paths.web_project.as_deref(), Some(paths.root.as_str()));
+    }
+
+    #[test]
+    fn session_mode_distinguishes_web_screens_and_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("screens")).unwrap();
+        assert_eq!(session_mode_in(dir.path()), SessionMode::Empty);
+
+        fs::write(dir.path().join("screens/home.html"), "<html></html>").unwrap();
+        assert_eq!(session_mode_in(dir.path()), SessionMode::ScreensOnly);
+
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(session_mode_in(dir.path()), SessionMode::WebProject);
+    }
+
+    #[test]
+    fn only_known_platforms_are_accepted() {
+        assert!(validate_platform("web").is_ok());
+        assert!(validate_platform("mobile").is_ok());
+        assert!(validate_platform("Mobile").is_err());
+        assert!(validate_platform("tablet").is_err());
+    }
+
+    #[test]
+    fn cache_sizes_and_selective_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(screens.join(".history/home")).unwrap();
+        fs::create_dir_all(screens.join(".thumbnails")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/react")).unwrap();
+        fs::write(screens.join(".history/home/1.html"), [0u8; 100]).unwrap();
+        fs::write(screens.join(".thumbnails/home.html.1.png"), [0u8; 40]).unwrap();
+        fs::write(dir.path().join(".thumbnails.png"), [0u8; 7]).unwrap();
+        fs::write(dir.path().join("node_modules/react/index.js"), [0u8; 25]).unwrap();
+        let cwd = dir.path().to_string_lossy().to_string();
+
+        let sizes = get_session_cache_sizes(cwd.clone());
+        assert_eq!(sizes.history_bytes, 100);
+        assert_eq!(sizes.thumbnails_bytes, 40);
+        assert_eq!(sizes.node_modules_bytes, 25);
+
+        assert!(clear_session_caches(cwd.clone(), vec!["logs".to_string()]).is_err());
+
+        let cleared =
+            clear_session_caches(cwd.clone(), vec!["history".to_string(), "thumbnails".to_string()]).unwrap();
+        assert_eq!(cleared.freed_bytes, 140);
+        assert_eq!(cleared.warnings.len(), 1);
+        assert!(!screens.join(".history").exists());
+        assert!(!screens.join(".thumbnails").exists());
+        assert!(dir.path().join("node_modules").exists());
+
+        let sizes = get_session_cache_sizes(cwd);
+        assert_eq!((sizes.history_bytes, sizes.thumbnails_bytes), (0, 0));
+    }
+
+    #[test]
+    fn iso_timestamp_matches_js_to_iso_string() {
+        use std::time::Duration;
+        assert_eq!(iso_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        // new Date(1709164800123).toISOString()
+        let leap_day = UNIX_EPOCH + Duration::from_millis(1_709_164_800_123);
+        assert_eq!(iso_timestamp(leap_day), "2024-02-29T00:00:00.123Z");
+        let late = UNIX_EPOCH + Duration::from_secs(1_735_689_599);
+        assert_eq!(iso_timestamp(late), "2024-12-31T23:59:59.000Z");
+    }
+
+    #[test]
+    fn import_requires_package_json() {
+        let source = tempfile::tempdir().unwrap();
+        let sessions = tempfile::tempdir().unwrap();
+        let dest = sessions.path().join("imported");
+        assert!(import_project_into(source.path(), &dest, false).is_err());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn load_migrates_unversioned_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        fs::write(
+            &path,
+            r#"{"sessions":[{"id":"s1","name":"Old","created_at":"2024-01-01","cwd":"/tmp/s1"}]}"#,
+        )
+        .unwrap();
+
+        let store = load_sessions_store_from(&path);
+        assert_eq!(store.schema_version, SESSIONS_SCHEMA_VERSION);
+        assert!(!store.sessions[0].favorite);
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["schema_version"], SESSIONS_SCHEMA_VERSION);
+        assert_eq!(written["sessions"][0]["favorite"], false);
+        assert!(written["sessions"][0].get("platform").is_some());
+    }
+
+    #[test]
+    fn legacy_layout_migration_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let session_dir = dir.path().join("s1");
+        fs::create_dir_all(&session_dir).unwrap();
+        let path = dir.path().join("sessions.json");
+        let legacy = serde_json::json!({
+            "schema_version": 1,
+            "sessions": [{"id": "s1", "name": "Old", "created_at": "2024-01-01", "cwd": session_dir}]
+        });
+        fs::write(&path, legacy.to_string()).unwrap();
+
+        let store = load_sessions_store_from(&path);
+        assert_eq!(store.sessions[0].platform.as_deref(), Some("web"));
+        assert!(session_dir.join("screens").is_dir());
+        let migrated = fs::read_to_string(&path).unwrap();
+
+        let again = load_sessions_store_from(&path);
+        assert_eq!(again.sessions[0].platform.as_deref(), Some("web"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), migrated);
+    }
+
+    #[test]
+    fn load_leaves_current_store_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        let content = format!(r#"{{"schema_version":{},"sessions":[]}}"#, SESSIONS_SCHEMA_VERSION);
+        fs::write(&path, &content).unwrap();
+
+        load_sessions_store_from(&path);
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn inspect_reads_manifest_without_extracting() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("session.zip");
+        write_zip(
+            &zip_path,
+            &[
+                (SESSION_ARCHIVE_META, r#"{"name":"Coffee app"}"#),
+                ("screens/home.html", "<html></html>"),
+            ],
+        );
+
+        let manifest = inspect_session_zip(zip_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(manifest.session_name.as_deref(), Some("Coffee app"));
+        assert_eq!(manifest.file_count, 2);
+        assert_eq!(manifest.total_uncompressed_size, 21 + 13);
+        assert!(manifest.unsafe_entries.is_empty());
+        assert!(!manifest.truncated);
+        assert!(!dir.path().join("screens").exists());
+    }
+
+    #[test]
+    fn inspect_flags_zip_slip_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("evil.zip");
+        write_zip(&zip_path, &[("../escape.html", "x"), ("ok.html", "y")]);
+
+        let manifest = inspect_session_zip(zip_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(manifest.session_name, None);
+        assert_eq!(manifest.unsafe_entries, vec!["../escape.html".to_string()]);
+    }
+}
@@ -0,0 +1,69 @@
+//! User settings persisted to `~/.dilag/settings.json`.
+//!
+//! Settings are read fresh on each access; the file is small and only written
+//! from explicit user actions, so there is no in-memory cache to invalidate.
+
+use crate::error::AppResult;
+use crate::paths::get_settings_file;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Settings {
+    /// Bash permission rules layered on top of the built-in defaults
+    #[serde(default)]
+    pub bash_permissions: HashMap<String, String>,
+    /// Last port the OpenCode server was started on, reused when still free
+    #[serde(default)]
+    pub opencode_port: Option<u16>,
+    /// Overrides for top-level `opencode.json` options (`share`, `autoupdate`)
+    #[serde(default)]
+    pub opencode_options: serde_json::Map<String, serde_json::Value>,
+    /// Session open when the app was last used, restored on launch
+    #[serde(default)]
+    pub last_active_session: Option<String>,
+    /// Give OpenCode its own `XDG_DATA_HOME` under the data dir. Keeps Dilag's
+    /// sessions and provider auth apart from a standalone OpenCode install, at
+    /// the cost of signing in to providers again. Off by default (shared data).
+    #[serde(default)]
+    pub isolate_opencode_data: bool,
+    /// Bind the OpenCode server on all interfaces so other devices on the
+    /// network can reach it. Off by default: the server has no authentication.
+    #[serde(default)]
+    pub bind_lan: bool,
+    /// Stop the OpenCode server after this many idle minutes; `None` disables it
+    #[serde(default)]
+    pub opencode_idle_timeout_mins: Option<u32>,
+    /// Main window placement from the last session (see `window_state.rs`)
+    #[serde(default)]
+    pub window_geometry: Option<crate::window_state::WindowGeometry>,
+    /// `"stable"` (default) or `"beta"`; see `updater.rs`
+    #[serde(default)]
+    pub update_channel: Option<String>,
+    /// `opencode.json` plugin list; `None` uses the built-in default
+    #[serde(default)]
+    pub opencode_plugins: Option<Vec<String>>,
+}
+
+/// Load settings from disk, falling back to defaults when missing or unreadable
+pub fn load_settings() -> Settings {
+    let file_path = get_settings_file();
+    if file_path.exists() {
+        let content = fs::read_to_string(&file_path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        Settings::default()
+    }
+}
+
+/// Write settings to disk, creating the data directory if needed
+pub fn save_settings(settings: &Settings) -> AppResult<()> {
+    let file_path = get_settings_file();
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(settings)?;
+    fs::write(&file_path, json)?;
+    Ok(())
+}
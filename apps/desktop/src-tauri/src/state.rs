@@ -1,9 +1,31 @@
+use crate::error::{AppError, AppResult};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
+use std::time::Instant;
 
 pub struct AppState {
     pub opencode_pid: Mutex<Option<u32>>,
     pub opencode_port: Mutex<Option<u16>>,
+    /// When the tracked OpenCode server was spawned
+    pub opencode_started_at: Mutex<Option<Instant>>,
+    /// Last `mark_opencode_activity` ping (or server start), for the idle auto-stop
+    pub opencode_last_activity: Mutex<Option<Instant>>,
+    /// Set when the idle auto-stop killed the server, so the next activity restarts it
+    pub opencode_idle_stopped: Mutex<bool>,
+    /// PID of the curl/bash child currently running for `install_dependencies`
+    pub install_pid: Mutex<Option<u32>>,
+    /// Set by `cancel_install_dependencies`, reset when an install starts
+    pub install_cancelled: Mutex<bool>,
+    /// Active design watchers keyed by session cwd (see `watcher.rs`)
+    pub session_watchers: Mutex<HashMap<String, notify::RecommendedWatcher>>,
+    /// Session cwds whose watcher events are held back during a bulk operation
+    pub paused_watches: Mutex<HashSet<String>>,
+    /// PID of another running Dilag that holds the instance lock (see `instance.rs`)
+    pub other_instance_pid: Mutex<Option<u32>>,
+    /// Why the app is busy (e.g. a generation is writing files), set by
+    /// `set_busy`; destructive commands refuse while it's set
+    pub busy_reason: Mutex<Option<String>>,
 }
 
 impl AppState {
@@ -11,6 +33,23 @@ impl AppState {
         Self {
             opencode_pid: Mutex::new(None),
             opencode_port: Mutex::new(None),
+            opencode_started_at: Mutex::new(None),
+            opencode_last_activity: Mutex::new(None),
+            opencode_idle_stopped: Mutex::new(false),
+            install_pid: Mutex::new(None),
+            install_cancelled: Mutex::new(false),
+            session_watchers: Mutex::new(HashMap::new()),
+            paused_watches: Mutex::new(HashSet::new()),
+            other_instance_pid: Mutex::new(None),
+            busy_reason: Mutex::new(None),
+        }
+    }
+
+    /// Fail with `AppError::Busy` while an operation marked by `set_busy` runs
+    pub fn ensure_not_busy(&self) -> AppResult<()> {
+        match self.busy_reason.lock().unwrap().clone() {
+            Some(reason) => Err(AppError::Busy(reason)),
+            None => Ok(()),
         }
     }
 }
@@ -32,6 +71,10 @@ pub struct SessionMeta {
     pub platform: Option<String>, // "web" (default) or "mobile"
     #[serde(default)]
     pub favorite: bool,
+    /// OpenCode server session holding this session's chat thread, when it
+    /// differs from `id`
+    #[serde(default)]
+    pub opencode_session_id: Option<String>,
 }
 
 /// Design file extracted from a session directory
@@ -45,10 +88,37 @@ pub struct DesignFile {
     /// Rule violations discovered by `screen_validator` — empty when the screen is clean.
     #[serde(default)]
     pub violations: Vec<crate::screen_validator::Violation>,
+    /// False when the file looks truncated (no `</html>` or unbalanced `<body>`),
+    /// e.g. while the agent is still writing it.
+    #[serde(default = "default_true")]
+    pub valid: bool,
+    /// Cached thumbnail for this version of the file (see `thumbnails.rs`), so
+    /// galleries can show an image instead of rendering the HTML.
+    #[serde(default)]
+    pub thumbnail_path: Option<String>,
+    /// Flow subfolder of `screens/` (e.g. `onboarding`), `None` for top-level screens
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// Local storage for sessions list
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SessionsStore {
+    /// Missing in files written before versioning, which reads as 0
+    #[serde(default)]
+    pub schema_version: u32,
     pub sessions: Vec<SessionMeta>,
 }
+
+impl Default for SessionsStore {
+    fn default() -> Self {
+        Self {
+            schema_version: crate::sessions::SESSIONS_SCHEMA_VERSION,
+            sessions: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Cached design thumbnails.
+//!
+//! Native capture isn't available (see `capture.rs`), so thumbnails are
+//! rendered by the frontend and handed back to be cached. Each one is stored
+//! next to its design as `.thumbnails/<filename>.<mtime>.png`, keyed by the
+//! source file's modified time so an edited design misses the cache.
+
+use crate::designs::resolve_design_files;
+use crate::error::{AppError, AppResult};
+use crate::project_files::resolve_session_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const THUMBNAIL_DIR: &str = ".thumbnails";
+
+/// Source modified time in milliseconds, used as the cache key
+fn source_mtime_ms(source: &Path) -> AppResult<u128> {
+    let modified = fs::metadata(source)?.modified()?;
+    Ok(modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0))
+}
+
+fn thumbnail_dir(source: &Path) -> PathBuf {
+    source
+        .parent()
+        .map(|p| p.join(THUMBNAIL_DIR))
+        .unwrap_or_else(|| PathBuf::from(THUMBNAIL_DIR))
+}
+
+fn source_filename(source: &Path) -> String {
+    source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Path of the thumbnail for the current version of `source`
+fn thumbnail_path(source: &Path) -> AppResult<PathBuf> {
+    let mtime = source_mtime_ms(source)?;
+    Ok(thumbnail_dir(source).join(format!("{}.{}.png", source_filename(source), mtime)))
+}
+
+/// The cached thumbnail for the current version of `source`, if there is one
+pub(crate) fn cached_thumbnail_path(source: &Path) -> Option<PathBuf> {
+    thumbnail_path(source).ok().filter(|p| p.is_file())
+}
+
+/// Remove thumbnails for older versions of `source`
+fn prune_stale(source: &Path, keep: &Path) {
+    let prefix = format!("{}.", source_filename(source));
+    if let Ok(entries) = fs::read_dir(thumbnail_dir(source)) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path != keep && name.starts_with(&prefix) && name.ends_with(".png") {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Resolve `file_path` to one of the session's designs, so thumbnails are
+/// never read or written next to an arbitrary file on disk
+fn resolve_design(session_cwd: &str, file_path: &str) -> AppResult<PathBuf> {
+    // The frontend passes absolute paths; anything outside the session fails here
+    let relative = Path::new(file_path)
+        .strip_prefix(session_cwd)
+        .map_err(|_| AppError::Custom(format!("Not a design in this session: {}", file_path)))?;
+    let path = resolve_session_path(session_cwd, &relative.to_string_lossy())?;
+    let is_design = resolve_design_files(Path::new(session_cwd))
+        .iter()
+        .any(|design| fs::canonicalize(design).is_ok_and(|design| design == path));
+    if !is_design {
+        return Err(AppError::Custom(format!("Not a design in this session: {}", file_path)));
+    }
+    Ok(path)
+}
+
+/// Cached thumbnail bytes for a design, or `None` if missing or out of date
+#[tauri::command]
+pub fn get_thumbnail(session_cwd: String, file_path: String) -> AppResult<Option<Vec<u8>>> {
+    let path = thumbnail_path(&resolve_design(&session_cwd, &file_path)?)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read(path)?))
+}
+
+/// Cache a PNG rendered by the frontend for the current version of a design
+#[tauri::command]
+pub fn save_thumbnail(session_cwd: String, file_path: String, png: Vec<u8>) -> AppResult<()> {
+    let source = resolve_design(&session_cwd, &file_path)?;
+    let path = thumbnail_path(&source)?;
+    fs::create_dir_all(thumbnail_dir(&source))?;
+    fs::write(&path, png)?;
+    prune_stale(&source, &path);
+    Ok(())
+}
+
+/// Designs in a session whose thumbnail is missing or stale, so the frontend
+/// only renders what changed. Covers the same files as `load_session_designs`.
+#[tauri::command]
+pub fn list_stale_thumbnails(session_cwd: String) -> Vec<String> {
+    let mut stale: Vec<String> = resolve_design_files(Path::new(&session_cwd))
+        .into_iter()
+        .filter(|path| cached_thumbnail_path(path).is_none())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    stale.sort();
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn set_mtime(path: &Path, time: SystemTime) {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn thumbnail_is_invalidated_when_source_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let screens = dir.path().join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        let design = screens.join("home.html");
+        fs::write(&design, "<html></html>").unwrap();
+        let design_path = design.to_string_lossy().to_string();
+        let session_cwd = dir.path().to_string_lossy().to_string();
+
+        assert_eq!(get_thumbnail(session_cwd.clone(), design_path.clone()).unwrap(), None);
+        assert_eq!(list_stale_thumbnails(session_cwd.clone()), vec![design_path.clone()]);
+
+        save_thumbnail(session_cwd.clone(), design_path.clone(), vec![1, 2, 3]).unwrap();
+        assert_eq!(get_thumbnail(session_cwd.clone(), design_path.clone()).unwrap(), Some(vec![1, 2, 3]));
+        assert!(cached_thumbnail_path(&design).is_some());
+        assert!(list_stale_thumbnails(session_cwd.clone()).is_empty());
+
+        // Editing the design changes its mtime, so the cached thumbnail no longer applies
+        set_mtime(&design, SystemTime::now() + Duration::from_secs(5));
+        assert_eq!(get_thumbnail(session_cwd.clone(), design_path.clone()).unwrap(), None);
+        assert_eq!(cached_thumbnail_path(&design), None);
+        assert_eq!(list_stale_thumbnails(session_cwd.clone()), vec![design_path.clone()]);
+
+        // Saving the new version prunes the old one
+        save_thumbnail(session_cwd.clone(), design_path.clone(), vec![4]).unwrap();
+        let cached: Vec<_> = fs::read_dir(screens.join(THUMBNAIL_DIR)).unwrap().collect();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(get_thumbnail(session_cwd, design_path).unwrap(), Some(vec![4]));
+    }
+
+    #[test]
+    fn flow_screens_are_listed_as_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let flow = dir.path().join("screens").join("onboarding");
+        fs::create_dir_all(&flow).unwrap();
+        fs::write(flow.join("welcome.html"), "<html></html>").unwrap();
+
+        let stale = list_stale_thumbnails(dir.path().to_string_lossy().to_string());
+        assert_eq!(stale.len(), 1);
+        assert!(stale[0].ends_with("welcome.html"));
+    }
+
+    #[test]
+    fn thumbnails_are_refused_outside_session_designs() {
+        let outer = tempfile::tempdir().unwrap();
+        let session = outer.path().join("session");
+        fs::create_dir_all(session.join("screens")).unwrap();
+        fs::write(session.join("package.json"), "{}").unwrap();
+        let elsewhere = outer.path().join("notes.html");
+        fs::write(&elsewhere, "<html></html>").unwrap();
+
+        let cwd = session.to_string_lossy().to_string();
+        for path in [elsewhere, session.join("package.json")] {
+            let path = path.to_string_lossy().to_string();
+            assert!(save_thumbnail(cwd.clone(), path.clone(), vec![1]).is_err());
+            assert!(get_thumbnail(cwd.clone(), path).is_err());
+        }
+        assert!(!outer.path().join(THUMBNAIL_DIR).exists());
+        assert!(!session.join(THUMBNAIL_DIR).exists());
+    }
+}
@@ -0,0 +1,194 @@
+//! Update channel selection and update checks.
+//!
+//! The updater plugin reads its endpoints from `tauri.conf.json`, so the
+//! chosen channel is applied by rewriting that plugin config before the app is
+//! built. Changing the channel therefore takes effect on the next launch.
+
+use crate::error::{AppError, AppResult};
+use crate::settings::{load_settings, save_settings};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::UpdaterExt;
+
+pub const UPDATE_CHANNELS: [&str; 2] = ["stable", "beta"];
+const DEFAULT_CHANNEL: &str = "stable";
+
+/// Beta builds publish their `latest.json` on a rolling `beta` release
+const BETA_ENDPOINT: &str = "https://github.com/noelrohi/dilag/releases/download/beta/latest.json";
+
+fn current_channel() -> String {
+    load_settings()
+        .update_channel
+        .filter(|c| UPDATE_CHANNELS.contains(&c.as_str()))
+        .unwrap_or_else(|| DEFAULT_CHANNEL.to_string())
+}
+
+/// Point the updater plugin config at `channel`'s feed. Stable keeps the
+/// endpoints from `tauri.conf.json`.
+fn apply_channel(plugins: &mut HashMap<String, serde_json::Value>, channel: &str) {
+    if channel != "beta" {
+        return;
+    }
+    if let Some(updater) = plugins.get_mut("updater").and_then(|u| u.as_object_mut()) {
+        updater.insert("endpoints".to_string(), serde_json::json!([BETA_ENDPOINT]));
+    }
+}
+
+/// Apply the persisted channel to the app config; call before building the app
+pub fn apply_update_channel<R: tauri::Runtime>(context: &mut tauri::Context<R>) {
+    let channel = current_channel();
+    println!("[setup] Update channel: {}", channel);
+    apply_channel(&mut context.config_mut().plugins.0, &channel);
+}
+
+#[tauri::command]
+pub fn get_update_channel() -> String {
+    current_channel()
+}
+
+/// Persist the update channel (`"stable"` or `"beta"`); used from the next launch
+#[tauri::command]
+pub fn set_update_channel(channel: String) -> AppResult<()> {
+    if !UPDATE_CHANNELS.contains(&channel.as_str()) {
+        return Err(format!(
+            "Unknown update channel '{}'; expected one of: {}",
+            channel,
+            UPDATE_CHANNELS.join(", ")
+        )
+        .into());
+    }
+    let mut settings = load_settings();
+    settings.update_channel = Some(channel);
+    save_settings(&settings)
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    pub version: Option<String>,
+    pub notes: Option<String>,
+    /// RFC 3339 publish date from the release feed
+    pub date: Option<String>,
+    /// Package size from the download server, when it reports one
+    pub download_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Separate "couldn't reach the feed" from other updater failures
+fn map_updater_error(e: tauri_plugin_updater::Error) -> AppError {
+    use tauri_plugin_updater::Error;
+    match e {
+        Error::Reqwest(_) | Error::Network(_) | Error::ReleaseNotFound => {
+            AppError::Custom(format!("Network error while checking for updates: {}", e))
+        }
+        other => AppError::Custom(format!("Update check failed: {}", other)),
+    }
+}
+
+async fn fetch_update(app: &AppHandle) -> AppResult<Option<tauri_plugin_updater::Update>> {
+    app.updater()
+        .map_err(map_updater_error)?
+        .check()
+        .await
+        .map_err(map_updater_error)
+}
+
+/// Size of the update package from a HEAD request; `None` if the server
+/// doesn't say or can't be reached quickly
+async fn download_size(url: &str) -> Option<u64> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .ok()?;
+    let response = client.head(url).send().await.ok()?;
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Check the configured channel for a newer version. "No update" is a normal
+/// result; failing to reach the feed is an error.
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> AppResult<UpdateInfo> {
+    let current_version = app.package_info().version.to_string();
+    let Some(update) = fetch_update(&app).await? else {
+        return Ok(UpdateInfo {
+            available: false,
+            current_version,
+            version: None,
+            notes: None,
+            date: None,
+            download_size: None,
+        });
+    };
+
+    Ok(UpdateInfo {
+        available: true,
+        current_version,
+        version: Some(update.version.clone()),
+        notes: update.body.clone(),
+        date: update.raw_json.get("pub_date").and_then(|d| d.as_str()).map(String::from),
+        download_size: download_size(update.download_url.as_str()).await,
+    })
+}
+
+/// Download and install the available update, emitting `update:progress`
+/// with `{ downloaded, total }` as chunks arrive. The frontend relaunches after.
+#[tauri::command]
+pub async fn download_and_install_update(app: AppHandle) -> AppResult<()> {
+    let update = fetch_update(&app)
+        .await?
+        .ok_or_else(|| AppError::Custom("No update available".to_string()))?;
+
+    let mut downloaded: u64 = 0;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("update:progress", UpdateProgress { downloaded, total });
+            },
+            || println!("[updater] Download finished, installing"),
+        )
+        .await
+        .map_err(|e| AppError::Custom(format!("Failed to install update: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugins() -> HashMap<String, serde_json::Value> {
+        HashMap::from([(
+            "updater".to_string(),
+            serde_json::json!({ "endpoints": ["https://example.com/stable.json"], "pubkey": "k" }),
+        )])
+    }
+
+    #[test]
+    fn beta_channel_swaps_endpoints_and_stable_keeps_them() {
+        let mut stable = plugins();
+        apply_channel(&mut stable, "stable");
+        assert_eq!(stable, plugins());
+
+        let mut beta = plugins();
+        apply_channel(&mut beta, "beta");
+        assert_eq!(beta["updater"]["endpoints"], serde_json::json!([BETA_ENDPOINT]));
+        assert_eq!(beta["updater"]["pubkey"], "k");
+    }
+
+    #[test]
+    fn unknown_channel_is_rejected() {
+        assert!(set_update_channel("nightly".to_string()).is_err());
+    }
+}
@@ -0,0 +1,478 @@
+//! Session file watcher.
+//!
+//! Pushes `design:created` / `design:updated` / `design:removed` events as the
+//! agent writes screens, so the frontend doesn't have to wait for the next poll.
+//! Writes are debounced, and files that are still missing `</html>` are re-read
+//! a few times before being emitted with `valid: false`. Bulk operations can
+//! pause a watch; resuming emits one `designs:reloaded` instead of the storm.
+//! `await_design_write` waits on a single file with its own short-lived watcher.
+//! Both cover the same files as `resolve_design_files`: the session root,
+//! `screens/` and its flow folders.
+
+use crate::designs::{design_folder, read_design_file, resolve_design_files};
+use crate::error::{AppError, AppResult};
+use crate::state::{AppState, DesignFile};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Quiet period after the last write before a file is read
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// Delay before re-reading a file that looks truncated
+const INCOMPLETE_RETRY: Duration = Duration::from_millis(750);
+/// Re-reads before an incomplete file is emitted anyway
+const MAX_INCOMPLETE_RETRIES: u32 = 8;
+/// How long the loop sleeps when nothing is pending
+const IDLE_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+struct DesignEvent {
+    session_cwd: String,
+    design: DesignFile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DesignRemovedEvent {
+    session_cwd: String,
+    filename: String,
+    /// Flow folder the design was in, `None` for top-level screens
+    folder: Option<String>,
+}
+
+fn is_paused(app: &AppHandle, session_cwd: &str) -> bool {
+    app.state::<AppState>()
+        .paused_watches
+        .lock()
+        .unwrap()
+        .contains(session_cwd)
+}
+
+/// Designs live in the session root, `screens/`, or a flow folder directly
+/// under it (the folders `design_dirs` lists; hidden caches are skipped)
+fn is_design_path(session_dir: &Path, path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "html")
+        && path.parent().is_some_and(|p| {
+            p == session_dir
+                || p == session_dir.join("screens")
+                || design_folder(session_dir, path).is_some_and(|f| !f.starts_with('.'))
+        })
+}
+
+/// Path relative to the session, so `onboarding/home.html` and `home.html`
+/// are told apart
+fn design_key(session_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(session_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn existing_design_names(session_dir: &Path) -> HashSet<String> {
+    resolve_design_files(session_dir)
+        .iter()
+        .map(|path| design_key(session_dir, path))
+        .collect()
+}
+
+/// Watch the session root for legacy flat designs and `screens/` recursively
+/// for flow folders; `is_design_path` drops anything deeper
+fn watch_design_dirs(watcher: &mut notify::RecommendedWatcher, session_dir: &Path) -> AppResult<()> {
+    let screens_dir = session_dir.join("screens");
+    for (dir, mode) in [
+        (session_dir, RecursiveMode::NonRecursive),
+        (screens_dir.as_path(), RecursiveMode::Recursive),
+    ] {
+        if dir.is_dir() {
+            watcher
+                .watch(dir, mode)
+                .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs on its own thread until the watcher (and with it the sender) is dropped
+fn run_event_loop(
+    app: AppHandle,
+    session_cwd: String,
+    session_dir: PathBuf,
+    rx: mpsc::Receiver<notify::Result<notify::Event>>,
+) {
+    let mut known = existing_design_names(&session_dir);
+    // path -> (when to read it, incomplete re-reads so far)
+    let mut pending: HashMap<PathBuf, (Instant, u32)> = HashMap::new();
+    // Designs changed while the watch was paused; indexed in one batch on resume
+    let mut missed: HashSet<PathBuf> = HashSet::new();
+
+    loop {
+        let now = Instant::now();
+        let wait = pending
+            .values()
+            .map(|(due, _)| due.saturating_duration_since(now))
+            .min()
+            .unwrap_or(IDLE_WAIT);
+        // Poll for the resume while paused changes are waiting to be indexed
+        let wait = if missed.is_empty() { wait } else { wait.min(DEBOUNCE) };
+
+        match rx.recv_timeout(wait) {
+            Ok(Ok(event)) if is_paused(&app, &session_cwd) => {
+                // The frontend reloads everything on resume; the index catches up then
+                for path in event.paths {
+                    if is_design_path(&session_dir, &path) {
+                        pending.remove(&path);
+                        missed.insert(path);
+                    }
+                }
+                continue;
+            }
+            Ok(Ok(event)) => {
+                for path in event.paths {
+                    if is_design_path(&session_dir, &path) {
+                        pending.insert(path, (Instant::now() + DEBOUNCE, 0));
+                    }
+                }
+            }
+            Ok(Err(e)) => eprintln!("[watcher] {}: {}", session_cwd, e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if !missed.is_empty() && !is_paused(&app, &session_cwd) {
+            known = existing_design_names(&session_dir);
+            let paths: Vec<PathBuf> = missed.drain().collect();
+            crate::search::update_index(&session_cwd, &paths);
+        }
+
+        let now = Instant::now();
+        let due: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, (at, _))| *at <= now)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut changed: Vec<PathBuf> = Vec::new();
+        for path in due {
+            let Some((_, retries)) = pending.remove(&path) else {
+                continue;
+            };
+            let key = design_key(&session_dir, &path);
+            let folder = design_folder(&session_dir, &path);
+
+            if !path.exists() {
+                changed.push(path.clone());
+                if known.remove(&key) {
+                    let _ = app.emit(
+                        "design:removed",
+                        DesignRemovedEvent {
+                            session_cwd: session_cwd.clone(),
+                            filename: path
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_default(),
+                            folder,
+                        },
+                    );
+                }
+                continue;
+            }
+
+            let Some(mut design) = read_design_file(&path) else {
+                continue;
+            };
+            design.folder = folder;
+            if !design.valid && retries < MAX_INCOMPLETE_RETRIES {
+                pending.insert(path, (now + INCOMPLETE_RETRY, retries + 1));
+                continue;
+            }
+
+            changed.push(path);
+            let event = if known.insert(key) {
+                "design:created"
+            } else {
+                "design:updated"
+            };
+            let _ = app.emit(
+                event,
+                DesignEvent {
+                    session_cwd: session_cwd.clone(),
+                    design,
+                },
+            );
+        }
+        crate::search::update_index(&session_cwd, &changed);
+    }
+}
+
+/// Block until `filename` is next created or rewritten and reads back
+/// complete, or until `timeout` passes. With a `folder` only that flow folder
+/// counts; otherwise the session root and `screens/`. Writes are debounced
+/// like the session watcher, and truncated reads are retried.
+fn wait_for_design_write(
+    session_dir: &Path,
+    folder: Option<&str>,
+    filename: &str,
+    timeout: Duration,
+) -> AppResult<DesignFile> {
+    let deadline = Instant::now() + timeout;
+    let screens_dir = session_dir.join("screens");
+    let targets = match folder {
+        Some(folder) => vec![screens_dir.join(folder).join(filename)],
+        None => vec![session_dir.join(filename), screens_dir.join(filename)],
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watch_design_dirs(&mut watcher, session_dir)?;
+
+    // Which target was written and when to read it
+    let mut pending: Option<(PathBuf, Instant)> = None;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(AppError::Timeout(format!(
+                "{} was not written within {} ms",
+                filename,
+                timeout.as_millis()
+            )));
+        }
+        let wake = pending.as_ref().map_or(deadline, |(_, due)| (*due).min(deadline));
+
+        match rx.recv_timeout(wake.saturating_duration_since(now)) {
+            Ok(Ok(event)) if event.kind.is_create() || event.kind.is_modify() => {
+                if let Some(path) = event.paths.into_iter().find(|p| targets.contains(p)) {
+                    pending = Some((path, Instant::now() + DEBOUNCE));
+                }
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("[watcher] {}: {}", session_dir.display(), e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err("Design watcher stopped unexpectedly".into())
+            }
+        }
+
+        let now = Instant::now();
+        if let Some((path, due)) = pending.take() {
+            if due > now {
+                pending = Some((path, due));
+            } else {
+                match read_design_file(&path) {
+                    Some(mut design) if design.valid => {
+                        design.folder = design_folder(session_dir, &path);
+                        return Ok(design);
+                    }
+                    _ => pending = Some((path, now + INCOMPLETE_RETRY)),
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Start watching a session for design changes. No-op if already watching.
+#[tauri::command]
+pub fn start_session_watch(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    session_cwd: String,
+) -> AppResult<()> {
+    let mut watchers = state.session_watchers.lock().unwrap();
+    if watchers.contains_key(&session_cwd) {
+        return Ok(());
+    }
+
+    let session_dir = fs::canonicalize(&session_cwd)?;
+    let screens_dir = session_dir.join("screens");
+    fs::create_dir_all(&screens_dir)?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watch_design_dirs(&mut watcher, &session_dir)?;
+
+    let thread_cwd = session_cwd.clone();
+    std::thread::spawn(move || run_event_loop(app, thread_cwd, session_dir, rx));
+
+    watchers.insert(session_cwd, watcher);
+    Ok(())
+}
+
+/// Hold back design events for a session during a bulk operation. Returns
+/// whether the session is being watched.
+pub(crate) fn pause_watch(state: &AppState, session_cwd: &str) -> bool {
+    if !state.session_watchers.lock().unwrap().contains_key(session_cwd) {
+        return false;
+    }
+    state.paused_watches.lock().unwrap().insert(session_cwd.to_string());
+    true
+}
+
+/// End a pause and emit a single `designs:reloaded` covering everything
+/// that changed meanwhile. The pause is held for one more debounce window
+/// so file events still in flight from the bulk operation are absorbed too.
+pub(crate) fn resume_watch(app: &AppHandle, state: &AppState, session_cwd: &str) {
+    if !state.paused_watches.lock().unwrap().contains(session_cwd) {
+        return;
+    }
+    let app = app.clone();
+    let session_cwd = session_cwd.to_string();
+    std::thread::spawn(move || {
+        std::thread::sleep(DEBOUNCE);
+        let removed = app
+            .state::<AppState>()
+            .paused_watches
+            .lock()
+            .unwrap()
+            .remove(&session_cwd);
+        if removed {
+            let _ = app.emit(
+                "designs:reloaded",
+                serde_json::json!({ "session_cwd": session_cwd }),
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub fn pause_session_watch(state: tauri::State<'_, AppState>, session_cwd: String) -> bool {
+    pause_watch(&state, &session_cwd)
+}
+
+#[tauri::command]
+pub fn resume_session_watch(app: AppHandle, state: tauri::State<'_, AppState>, session_cwd: String) {
+    resume_watch(&app, &state, &session_cwd);
+}
+
+/// Resolve once the agent has finished rewriting `filename` (closing
+/// `</html>` present), as a precise "screen regenerated" signal. Pass the
+/// design's `folder` for a flow screen. Only writes after the call count.
+/// Fails with `AppError::Timeout` after `timeout_ms`.
+#[tauri::command]
+pub async fn await_design_write(
+    session_cwd: String,
+    filename: String,
+    folder: Option<String>,
+    timeout_ms: u64,
+) -> AppResult<DesignFile> {
+    let is_plain_name = |name: &str| {
+        !name.is_empty() && !name.contains(['/', '\\']) && !name.contains("..") && !name.starts_with('.')
+    };
+    if !is_plain_name(&filename) || !filename.ends_with(".html") {
+        return Err(format!("Invalid design filename: {}", filename).into());
+    }
+    if let Some(folder) = folder.as_deref().filter(|f| !is_plain_name(f)) {
+        return Err(format!("Invalid flow folder: {}", folder).into());
+    }
+    let session_dir = fs::canonicalize(&session_cwd)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        wait_for_design_write(
+            &session_dir,
+            folder.as_deref(),
+            &filename,
+            Duration::from_millis(timeout_ms),
+        )
+    })
+    .await
+    .map_err(|e| format!("Design wait failed: {}", e))?
+}
+
+/// Stop watching a session. Returns whether a watch was running.
+#[tauri::command]
+pub fn stop_session_watch(state: tauri::State<'_, AppState>, session_cwd: String) -> bool {
+    // Dropping the watcher closes the channel, which ends the event loop thread
+    state.paused_watches.lock().unwrap().remove(&session_cwd);
+    state
+        .session_watchers
+        .lock()
+        .unwrap()
+        .remove(&session_cwd)
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn design_paths_are_html_in_root_or_screens() {
+        let root = Path::new("/sessions/abc");
+        assert!(is_design_path(root, &root.join("home.html")));
+        assert!(is_design_path(root, &root.join("screens").join("home.html")));
+        assert!(!is_design_path(root, &root.join("screens").join("notes.md")));
+        assert!(is_design_path(root, &root.join("screens/onboarding/welcome.html")));
+        assert!(!is_design_path(root, &root.join("screens/.thumbnails/home.html")));
+        assert!(!is_design_path(root, &root.join("screens/.history/home/1.html")));
+        assert!(!is_design_path(root, &root.join("screens/onboarding/old/welcome.html")));
+        assert!(!is_design_path(root, Path::new("/sessions/other/home.html")));
+    }
+
+    #[test]
+    fn waits_for_complete_write_of_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let session_dir = fs::canonicalize(dir.path()).unwrap();
+        let screens = session_dir.join("screens");
+        fs::create_dir_all(&screens).unwrap();
+        fs::write(screens.join("home.html"), "<html><body>old</body></html>").unwrap();
+
+        let writer_dir = screens.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            fs::write(writer_dir.join("other.html"), "<html><body></body></html>").unwrap();
+            fs::write(writer_dir.join("home.html"), "<html><body>new").unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            fs::write(writer_dir.join("home.html"), "<html><body>new</body></html>").unwrap();
+        });
+
+        let design =
+            wait_for_design_write(&session_dir, None, "home.html", Duration::from_secs(5)).unwrap();
+        writer.join().unwrap();
+        assert_eq!(design.filename, "home.html");
+        assert!(design.html.contains("new</body></html>"));
+    }
+
+    #[test]
+    fn waits_on_a_flow_screen() {
+        let dir = tempfile::tempdir().unwrap();
+        let session_dir = fs::canonicalize(dir.path()).unwrap();
+        let flow = session_dir.join("screens").join("onboarding");
+        fs::create_dir_all(&flow).unwrap();
+
+        let writer_dir = session_dir.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            // Same name at the top level doesn't count
+            fs::write(writer_dir.join("screens/welcome.html"), "<html><body></body></html>").unwrap();
+            fs::write(writer_dir.join("screens/onboarding/welcome.html"), "<html><body>hi</body></html>")
+                .unwrap();
+        });
+
+        let design = wait_for_design_write(
+            &session_dir,
+            Some("onboarding"),
+            "welcome.html",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        writer.join().unwrap();
+        assert_eq!(design.folder.as_deref(), Some("onboarding"));
+        assert!(design.html.contains("hi"));
+    }
+
+    #[test]
+    fn times_out_without_a_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let session_dir = fs::canonicalize(dir.path()).unwrap();
+        let err = wait_for_design_write(&session_dir, None, "home.html", Duration::from_millis(50))
+            .unwrap_err();
+        assert_eq!(err.code(), "timeout");
+    }
+}
@@ -0,0 +1,199 @@
+//! Main window geometry, persisted in settings across restarts.
+//!
+//! Moves and resizes only update an in-memory copy; it's written to
+//! `settings.json` when the window closes or the app exits, so dragging the
+//! window doesn't hammer the disk.
+
+use crate::settings::{load_settings, save_settings};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Monitor, Runtime, Window};
+
+/// Window placement in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub maximized: bool,
+}
+
+/// A monitor's work area in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Rect {
+    fn from_monitor(monitor: &Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let position = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Latest geometry seen since launch, flushed by `save_window_geometry`
+static PENDING: Mutex<Option<WindowGeometry>> = Mutex::new(None);
+
+/// Fit a saved geometry onto the connected monitors. A window whose top-left
+/// corner is on no monitor (e.g. one that was unplugged) is centered on the
+/// first monitor, which the caller makes the primary one; sizes are shrunk to fit.
+fn fit_to_monitors(geometry: WindowGeometry, monitors: &[Rect]) -> WindowGeometry {
+    let Some(monitor) = monitors
+        .iter()
+        .find(|m| m.contains(geometry.x, geometry.y))
+        .or_else(|| monitors.first())
+    else {
+        return geometry;
+    };
+
+    let width = geometry.width.min(monitor.width);
+    let height = geometry.height.min(monitor.height);
+    let (x, y) = if monitor.contains(geometry.x, geometry.y) {
+        (
+            geometry.x.clamp(monitor.x, monitor.x + monitor.width - width),
+            geometry.y.clamp(monitor.y, monitor.y + monitor.height - height),
+        )
+    } else {
+        (
+            monitor.x + (monitor.width - width) / 2.0,
+            monitor.y + (monitor.height - height) / 2.0,
+        )
+    };
+
+    WindowGeometry {
+        x,
+        y,
+        width,
+        height,
+        maximized: geometry.maximized,
+    }
+}
+
+/// Saved geometry adjusted to the current monitors, if any was saved
+pub fn restore_geometry<R: Runtime>(app: &AppHandle<R>) -> Option<WindowGeometry> {
+    let saved = load_settings().window_geometry?;
+    let mut monitors: Vec<Rect> = app
+        .available_monitors()
+        .map(|ms| ms.iter().map(Rect::from_monitor).collect())
+        .unwrap_or_default();
+    // Fallback placement goes on the primary monitor
+    if let Some(primary) = app.primary_monitor().ok().flatten().map(|m| Rect::from_monitor(&m)) {
+        monitors.retain(|m| *m != primary);
+        monitors.insert(0, primary);
+    }
+    Some(fit_to_monitors(saved, &monitors))
+}
+
+/// Record the window's current geometry. While maximized only the flag is
+/// updated, so un-maximizing after a restart returns to the last normal size.
+pub fn track_window_geometry<R: Runtime>(window: &Window<R>) {
+    let maximized = window.is_maximized().unwrap_or(false);
+    let mut pending = PENDING.lock().unwrap();
+    if maximized {
+        if let Some(mut geometry) = pending.or(load_settings().window_geometry) {
+            geometry.maximized = true;
+            *pending = Some(geometry);
+        }
+        return;
+    }
+
+    let (Ok(scale), Ok(position), Ok(size)) =
+        (window.scale_factor(), window.outer_position(), window.inner_size())
+    else {
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    let size = size.to_logical::<f64>(scale);
+    *pending = Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+    });
+}
+
+/// Persist the tracked geometry, if it changed since launch
+pub fn save_window_geometry() {
+    let Some(geometry) = PENDING.lock().unwrap().take() else {
+        return;
+    };
+    let mut settings = load_settings();
+    if settings.window_geometry == Some(geometry) {
+        return;
+    }
+    settings.window_geometry = Some(geometry);
+    if let Err(e) = save_settings(&settings) {
+        println!("[window] Failed to save geometry: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: Rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 1440.0,
+        height: 900.0,
+    };
+    const SECONDARY: Rect = Rect {
+        x: 1440.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1080.0,
+    };
+
+    fn geometry(x: f64, y: f64, width: f64, height: f64) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn visible_geometry_is_kept() {
+        let saved = geometry(1600.0, 100.0, 1200.0, 800.0);
+        assert_eq!(fit_to_monitors(saved, &[PRIMARY, SECONDARY]), saved);
+    }
+
+    #[test]
+    fn geometry_on_missing_monitor_is_centered_on_primary() {
+        let saved = geometry(1600.0, 100.0, 1200.0, 800.0);
+        assert_eq!(
+            fit_to_monitors(saved, &[PRIMARY]),
+            geometry(120.0, 50.0, 1200.0, 800.0)
+        );
+    }
+
+    #[test]
+    fn oversized_or_overhanging_geometry_is_clamped() {
+        assert_eq!(
+            fit_to_monitors(geometry(10.0, 10.0, 2000.0, 1000.0), &[PRIMARY]),
+            geometry(0.0, 0.0, 1440.0, 900.0)
+        );
+        assert_eq!(
+            fit_to_monitors(geometry(1000.0, 600.0, 800.0, 600.0), &[PRIMARY]),
+            geometry(640.0, 300.0, 800.0, 600.0)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { cn } from "@/lib/utils";
+import { getErrorMessage } from "@/lib/app-error";
 
 type SetupStage = "checking" | "missing" | "installing" | "installed" | "error";
 
@@ -43,7 +44,7 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
       setTimeout(onComplete, 600);
     } catch (err) {
       setStage("error");
-      setErrorMessage(err instanceof Error ? err.message : String(err));
+      setErrorMessage(getErrorMessage(err));
     }
   };
 
@@ -68,10 +69,15 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
       }
     } catch (err) {
       setStage("error");
-      setErrorMessage(err instanceof Error ? err.message : String(err));
+      setErrorMessage(getErrorMessage(err));
     }
   };
 
+  const handleCancelInstall = async () => {
+    // install_dependencies resolves with a cancelled result, which moves us to "error"
+    await invoke("cancel_install_dependencies").catch(() => {});
+  };
+
   useEffect(() => {
     checkDependencies();
   }, []);
@@ -134,6 +140,17 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
         </div>
 
         {/* Actions */}
+        {stage === "installing" && (
+          <div className="mt-8 flex flex-col items-center">
+            <button
+              onClick={handleCancelInstall}
+              className="text-xs text-muted-foreground/60 hover:text-muted-foreground transition-colors"
+            >
+              Cancel
+            </button>
+          </div>
+        )}
+
         {(stage === "missing" || stage === "error") && (
           <div className="mt-8 flex flex-col items-center gap-3">
             <button
@@ -1,11 +1,12 @@
-import { memo, useCallback, useEffect, useRef, useMemo } from "react";
+import { memo, useCallback, useEffect, useRef, useMemo, useState } from "react";
 import { Handle, Position, type NodeProps } from "@xyflow/react";
 import { IPhoneFrame } from "@/components/blocks/preview/iphone-frame";
 import { ViolationBadge } from "@/components/blocks/preview/violation-badge";
-import type { DesignFile } from "@/hooks/use-designs";
+import { getDesignPath, type DesignFile } from "@/hooks/use-designs";
+import { useThumbnailSrc } from "@/hooks/use-thumbnails";
 import { ContextMenu, ContextMenuContent, ContextMenuItem, ContextMenuSeparator, ContextMenuTrigger, ContextMenuShortcut } from "@dilag/ui/context-menu";
 import { Copy, Code, Download, TrashBinMinimalistic, FolderOpen, ChatRoundDots, Gallery } from "@solar-icons/react";
-import { copyFilePath, copyToClipboard, downloadHtml, exportAsPng } from "@/lib/design-export";
+import { copyFilePath, copyToClipboard, downloadHtml, duplicateDesign, exportAsPng, exportThemeVariants, saveHtmlAs } from "@/lib/design-export";
 import { CodeViewerDialog } from "@/components/blocks/dialogs/dialog-code-viewer";
 import { injectInspector, type ElementInspectorMessage } from "@/lib/element-inspector";
 import { 
@@ -74,9 +75,13 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
     return injectInspector(withScrollbar);
   }, [design.html]);
 
-  const filePath = sessionCwd
-    ? `${sessionCwd}/screens/${design.filename}`
-    : undefined;
+  const filePath = sessionCwd ? getDesignPath(sessionCwd, design) : undefined;
+
+  // Show the cached thumbnail until the user interacts with the screen, then
+  // mount the live iframe (needed for the element inspector) and keep it
+  const thumbnailSrc = useThumbnailSrc(sessionCwd, filePath, design.thumbnail_path);
+  const [live, setLive] = useState(false);
+  const showThumbnail = !!thumbnailSrc && !live && !selected;
 
   // Handle messages from iframe
   useEffect(() => {
@@ -189,10 +194,18 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
     if (filePath) copyFilePath(filePath);
   }, [filePath]);
 
+  const handleDuplicate = useCallback(() => {
+    if (filePath) duplicateDesign(filePath);
+  }, [filePath]);
+
   const handleDownload = useCallback(() => {
     downloadHtml({ html: design.html, title: design.title });
   }, [design.html, design.title]);
 
+  const handleSaveAs = useCallback(() => {
+    if (filePath) saveHtmlAs(filePath, design.title);
+  }, [filePath, design.title]);
+
   const handleExportPng = useCallback(() => {
     const dimensions = isMobile
       ? {
@@ -208,6 +221,21 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
     });
   }, [design.html, design.title, isMobile, mobileViewport?.height, mobileViewport?.width]);
 
+  const handleExportThemes = useCallback(() => {
+    const dimensions = isMobile
+      ? {
+          width: mobileViewport?.width ?? DEFAULT_MOBILE_SCREEN_WIDTH,
+          height: mobileViewport?.height ?? DEFAULT_MOBILE_SCREEN_HEIGHT,
+        }
+      : { width: 1280, height: 800 };
+    exportThemeVariants({
+      html: design.html,
+      title: design.title,
+      ...dimensions,
+      scale: 2,
+    });
+  }, [design.html, design.title, isMobile, mobileViewport?.height, mobileViewport?.width]);
+
   const handleDelete = useCallback(() => {
     // Clear selection if this screen has the selected element
     if (selectedElement?.screenId === id) {
@@ -249,7 +277,7 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
           </div>
 
           {/* Frame content - selection ring wraps only this */}
-          <div className="relative drag-handle">
+          <div className="relative drag-handle" onMouseEnter={() => setLive(true)}>
             {/* Selection ring - only around the frame, not the title */}
             {selected && (
               <div className="absolute -inset-2 border-2 border-primary rounded-2xl pointer-events-none z-10" />
@@ -275,20 +303,29 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
                       offset={iframeOffset}
                     />
                   )}
-                  <iframe
-                    ref={iframeRef}
-                    data-screen-id={id}
-                    srcDoc={preparedHtml}
-                    className="block w-full h-full border-0"
-                    sandbox="allow-scripts"
-                    title={design.title}
-                    style={{
-                      width: mobileViewport?.width ?? DEFAULT_MOBILE_SCREEN_WIDTH,
-                      height: mobileViewport?.height ?? DEFAULT_MOBILE_SCREEN_HEIGHT,
-                      transform: `scale(${scale})`,
-                      transformOrigin: "top left",
-                    }}
-                  />
+                  {showThumbnail ? (
+                    <img
+                      src={thumbnailSrc ?? undefined}
+                      alt={design.title}
+                      draggable={false}
+                      className="block w-full h-full object-cover object-top"
+                    />
+                  ) : (
+                    <iframe
+                      ref={iframeRef}
+                      data-screen-id={id}
+                      srcDoc={preparedHtml}
+                      className="block w-full h-full border-0"
+                      sandbox="allow-scripts"
+                      title={design.title}
+                      style={{
+                        width: mobileViewport?.width ?? DEFAULT_MOBILE_SCREEN_WIDTH,
+                        height: mobileViewport?.height ?? DEFAULT_MOBILE_SCREEN_HEIGHT,
+                        transform: `scale(${scale})`,
+                        transformOrigin: "top left",
+                      }}
+                    />
+                  )}
                 </div>
                 {showSelectedHighlight && selectedElement && onEditElementWithAI && (
                   <ElementSelectionMenu
@@ -334,20 +371,29 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
                       offset={iframeOffset}
                     />
                   )}
-                  <iframe
-                    ref={iframeRef}
-                    data-screen-id={id}
-                    srcDoc={preparedHtml}
-                    className="block w-full h-full border-0"
-                    sandbox="allow-scripts"
-                    title={design.title}
-                    style={{
-                      width: 1280,
-                      height: 800,
-                      transform: `scale(${WEB_SCALE})`,
-                      transformOrigin: "top left",
-                    }}
-                  />
+                  {showThumbnail ? (
+                    <img
+                      src={thumbnailSrc ?? undefined}
+                      alt={design.title}
+                      draggable={false}
+                      className="block w-full h-full object-cover object-top"
+                    />
+                  ) : (
+                    <iframe
+                      ref={iframeRef}
+                      data-screen-id={id}
+                      srcDoc={preparedHtml}
+                      className="block w-full h-full border-0"
+                      sandbox="allow-scripts"
+                      title={design.title}
+                      style={{
+                        width: 1280,
+                        height: 800,
+                        transform: `scale(${WEB_SCALE})`,
+                        transformOrigin: "top left",
+                      }}
+                    />
+                  )}
                 </div>
                 {showSelectedHighlight && selectedElement && onEditElementWithAI && (
                   <ElementSelectionMenu
@@ -397,6 +443,12 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
             Copy path
           </ContextMenuItem>
         )}
+        {filePath && (
+          <ContextMenuItem onClick={handleDuplicate}>
+            <Copy size={16} className="mr-2" />
+            Duplicate
+          </ContextMenuItem>
+        )}
         <ContextMenuSeparator />
         <CodeViewerDialog code={design.html} title={design.title}>
           <ContextMenuItem onSelect={(e) => e.preventDefault()}>
@@ -409,10 +461,20 @@ function ScreenNodeComponent({ id, data, selected }: NodeProps) {
           <Download size={16} className="mr-2" />
           Download HTML
         </ContextMenuItem>
+        {filePath && (
+          <ContextMenuItem onClick={handleSaveAs}>
+            <Download size={16} className="mr-2" />
+            Save HTML As...
+          </ContextMenuItem>
+        )}
         <ContextMenuItem onClick={handleExportPng}>
           <Gallery size={16} className="mr-2" />
           Export as PNG
         </ContextMenuItem>
+        <ContextMenuItem onClick={handleExportThemes}>
+          <Gallery size={16} className="mr-2" />
+          Export Light &amp; Dark PNGs
+        </ContextMenuItem>
         {onDelete && (
           <>
             <ContextMenuSeparator />
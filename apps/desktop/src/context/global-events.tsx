@@ -18,6 +18,7 @@ export type {
   ToolState,
   SnapshotFileDiff as FileDiff,
 } from "@opencode-ai/sdk/v2/client";
+import { getErrorMessage } from "@/lib/app-error";
 
 declare global {
   interface Window {
@@ -288,7 +289,7 @@ export function GlobalEventsProvider({ children }: { children: ReactNode }) {
         console.error("[GlobalEvents] Server start error:", err);
         if (mountedRef.current) {
           setIsServerReady(false);
-          setServerError(err instanceof Error ? err.message : String(err));
+          setServerError(getErrorMessage(err));
           setConnectionStatus("disconnected");
         }
       }
@@ -1,4 +1,5 @@
 import { createContext, useContext, useEffect, useRef, type ReactNode } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { toast } from "sonner";
 import { Howl } from "howler";
 import { useAllSessionStatuses, useSessionStore, type SessionStatus } from "@/context/session-store";
@@ -74,6 +75,22 @@ export function NotificationProvider({ children }: NotificationProviderProps) {
     }
   }, [sessionStatuses]);
 
+  // Dock badge with the number of generating sessions while the app is in the
+  // background (the backend clears it when the window regains focus), and the
+  // busy flag that blocks resets and deletes while files are being written
+  useEffect(() => {
+    const generating = Object.values(sessionStatuses).filter(
+      (status) => status === "running" || status === "busy"
+    ).length;
+    const label = generating > 0 && !document.hasFocus() ? String(generating) : null;
+    invoke("set_dock_badge", { label }).catch(console.error);
+    if (generating > 0) {
+      invoke("set_busy", { reason: "Designs are being generated" }).catch(console.error);
+    } else {
+      invoke("clear_busy").catch(console.error);
+    }
+  }, [sessionStatuses]);
+
   const value: NotificationContextValue = {
     playComplete: () => completionAudio.play(),
     playError: () => errorAudio.play(),
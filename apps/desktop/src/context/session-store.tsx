@@ -49,6 +49,7 @@ export interface SessionMeta {
   parentID?: string; // Reference to parent session if forked
   platform?: Platform; // "web" (default) or "mobile"
   favorite?: boolean;
+  opencode_session_id?: string; // OpenCode session holding the chat, when it differs from id
 }
 
 // Revert state for a session
@@ -1,6 +1,7 @@
 import { createContext, useContext, useState, useEffect, useCallback, useRef, type ReactNode } from "react";
 import { check, type Update, type DownloadEvent } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
+import { invoke } from "@tauri-apps/api/core";
 import { toast } from "sonner";
 
 export interface UpdateInfo {
@@ -87,7 +88,12 @@ export function UpdaterProvider({ children }: UpdaterProviderProps) {
         
         // Show feedback only when manually checking (not silent)
         if (!silent) {
-          toast.success("You're on the latest version");
+          const channel = await invoke<string>("get_update_channel").catch(() => "stable");
+          toast.success(
+            channel === "stable"
+              ? "You're on the latest version"
+              : `You're on the latest ${channel} version`
+          );
         }
       }
     } catch (error) {
@@ -25,6 +25,7 @@ export function makeDesignFile(overrides: Partial<DesignFile> = {}): DesignFile
     html: "<!DOCTYPE html><html><body></body></html>",
     modified_at: 1_700_000_000,
     violations: [],
+    valid: true,
     ...overrides,
   };
 }
@@ -1,5 +1,7 @@
-import { useQuery } from "@tanstack/react-query";
+import { useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 export type ViolationRule =
   | "keyframes"
@@ -21,6 +23,18 @@ export interface DesignFile {
   html: string;
   modified_at: number;
   violations: Violation[];
+  /** False when the file looks truncated (still being written) */
+  valid: boolean;
+  /** Cached thumbnail for this version, if any; fall back to rendering `html` */
+  thumbnail_path?: string | null;
+  /** Flow subfolder of `screens/`, or null for top-level screens */
+  folder?: string | null;
+}
+
+/** Absolute path of a design's HTML file */
+export function getDesignPath(sessionCwd: string, design: Pick<DesignFile, "filename" | "folder">) {
+  const dir = design.folder ? `screens/${design.folder}` : "screens";
+  return `${sessionCwd}/${dir}/${design.filename}`;
 }
 
 async function loadSessionDesigns(sessionCwd: string): Promise<DesignFile[]> {
@@ -53,3 +67,33 @@ export function useSessionDesigns(sessionCwd: string | undefined) {
     staleTime: 1000, // Consider data stale after 1 second
   });
 }
+
+/**
+ * Watch a session's files and refresh its designs as soon as the agent
+ * writes a screen, instead of waiting for the next poll
+ */
+export function useSessionDesignWatch(sessionCwd: string | undefined) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    if (!sessionCwd) return;
+
+    invoke("start_session_watch", { sessionCwd }).catch(console.error);
+
+    const refresh = (event: { payload: { session_cwd: string } }) => {
+      if (event.payload.session_cwd !== sessionCwd) return;
+      queryClient.invalidateQueries({ queryKey: designKeys.session(sessionCwd) });
+    };
+    const unlisteners = [
+      listen("design:created", refresh),
+      listen("design:updated", refresh),
+      listen("design:removed", refresh),
+      listen("designs:reloaded", refresh),
+    ];
+
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+      invoke("stop_session_watch", { sessionCwd }).catch(console.error);
+    };
+  }, [sessionCwd, queryClient]);
+}
@@ -0,0 +1,22 @@
+import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "sonner";
+
+/**
+ * Warn when another running Dilag owns the data directory. The backend
+ * records this during setup, before any page has loaded, so it's read on
+ * mount instead of being pushed as an event.
+ */
+export function useInstanceGuard() {
+  useEffect(() => {
+    invoke<number | null>("get_other_instance_pid")
+      .then((pid) => {
+        if (pid === null) return;
+        toast.warning("Dilag is already running", {
+          description: `Another instance (pid ${pid}) is using the same data. Close one of them to avoid conflicting changes.`,
+          duration: Infinity,
+        });
+      })
+      .catch(console.error);
+  }, []);
+}
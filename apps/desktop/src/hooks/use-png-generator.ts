@@ -1,7 +1,7 @@
 import { useEffect, useRef } from "react";
 import { stat } from "@tauri-apps/plugin-fs";
 import { generatePngToPath } from "@/lib/design-export";
-import type { DesignFile } from "./use-designs";
+import { getDesignPath, type DesignFile } from "./use-designs";
 
 /**
  * Auto-generates PNG assets for designs when HTML is newer than PNG
@@ -23,7 +23,7 @@ export function usePngGenerator(
         : { width: 1280, height: 800 };
 
     const generate = async (design: DesignFile) => {
-      const pngPath = `${sessionCwd}/screens/${design.filename.replace(".html", ".png")}`;
+      const pngPath = getDesignPath(sessionCwd, design).replace(/\.html$/, ".png");
 
       // Skip if already generating (mark immediately to prevent race condition)
       if (generating.current.has(pngPath)) return;
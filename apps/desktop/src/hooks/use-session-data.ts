@@ -30,10 +30,66 @@ async function toggleSessionFavorite(sessionId: string): Promise<boolean> {
   return invoke<boolean>("toggle_session_favorite", { sessionId });
 }
 
+export async function getSession(sessionId: string): Promise<SessionMeta> {
+  return invoke<SessionMeta>("get_session", { sessionId });
+}
+
+/** Switch a session between web and mobile; existing screens are kept as-is */
+export async function setSessionPlatform(sessionId: string, platform: "web" | "mobile"): Promise<void> {
+  return invoke<void>("set_session_platform", { sessionId, platform });
+}
+
+/** Persist which OpenCode session holds this session's chat thread */
+export async function setOpencodeSessionId(sessionId: string, opencodeId: string): Promise<void> {
+  return invoke<void>("set_opencode_session_id", { sessionId, opencodeId });
+}
+
+export interface CacheSizes {
+  history_bytes: number;
+  thumbnails_bytes: number;
+  node_modules_bytes: number;
+}
+
+export type SessionCache = "history" | "thumbnails" | "node_modules";
+
+export async function getSessionCacheSizes(sessionCwd: string): Promise<CacheSizes> {
+  return invoke<CacheSizes>("get_session_cache_sizes", { sessionCwd });
+}
+
+/** Delete the given caches; warnings note what can't be recovered (e.g. version history) */
+export async function clearSessionCaches(
+  sessionCwd: string,
+  which: SessionCache[],
+): Promise<{ freed_bytes: number; warnings: string[] }> {
+  return invoke("clear_session_caches", { sessionCwd, which });
+}
+
+export async function setLastActiveSession(sessionId: string): Promise<void> {
+  return invoke<void>("set_last_active_session", { sessionId });
+}
+
+/** Last opened session id, or null if none or it was deleted */
+export async function getLastActiveSession(): Promise<string | null> {
+  return invoke<string | null>("get_last_active_session");
+}
+
 export async function createSessionDir(sessionId: string): Promise<string> {
   return invoke<string>("create_session_dir", { sessionId });
 }
 
+/**
+ * Copy (or symlink with `link`) an existing web project into a new session.
+ * The session is registered with `platform: "web"`; create its OpenCode
+ * session in the returned cwd and record it with `setOpencodeSessionId`.
+ */
+export async function importWebProject(
+  sourceDir: string,
+  name: string,
+  link = false
+): Promise<SessionMeta> {
+  return invoke<SessionMeta>("import_web_project", { sourceDir, name, link });
+}
+
 /**
  * Hook to fetch the sessions list
  * Uses React Query for caching and automatic refetching
@@ -654,6 +654,11 @@ export function useSessions() {
           };
         }
 
+        // Counts as activity for the idle auto-stop, and restarts the server if it was stopped
+        await import("@tauri-apps/api/core")
+          .then(({ invoke }) => invoke("mark_opencode_activity"))
+          .catch((err) => console.warn("[sendMessage] mark_opencode_activity failed:", err));
+
         console.log("[sendMessage] calling sdk.session.prompt with:", { sessionID: currentSessionId, agent: agentName, model, variant, partsCount: parts.length });
         sdk.session.prompt({
           sessionID: currentSessionId,
@@ -0,0 +1,87 @@
+import { useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { designKeys, type DesignFile } from "@/hooks/use-designs";
+import { renderHtmlToPng } from "@/lib/design-export";
+
+/** Thumbnails are rendered at a fraction of the screen size */
+const THUMBNAIL_SCALE = 0.5;
+
+/** Whether `path` (as returned by the backend) is this design's file */
+function isDesignPath(path: string, design: DesignFile) {
+  const relative = design.folder ? `${design.folder}/${design.filename}` : design.filename;
+  return path.replace(/\\/g, "/").endsWith(`/${relative}`);
+}
+
+/**
+ * Fill the session's thumbnail cache in the background: render every design
+ * whose thumbnail is missing or stale, save it, then refetch the designs so
+ * `thumbnail_path` is populated. Native capture isn't available, so this is
+ * the only place thumbnails are produced.
+ */
+export function useThumbnailCache(sessionCwd: string | undefined, designs: DesignFile[]) {
+  const queryClient = useQueryClient();
+  // Re-run only when the set of versions changes, not on every refetch
+  const versionKey = designs.map((d) => `${d.folder ?? ""}/${d.filename}@${d.modified_at}`).join("|");
+
+  useEffect(() => {
+    if (!sessionCwd || designs.length === 0) return;
+    let cancelled = false;
+
+    (async () => {
+      const stale = await invoke<string[]>("list_stale_thumbnails", { sessionCwd });
+      let saved = 0;
+      for (const path of stale) {
+        if (cancelled) return;
+        const design = designs.find((d) => isDesignPath(path, d));
+        // Truncated files are still being written; the watcher will bring them back
+        if (!design || !design.valid) continue;
+        const size =
+          design.screen_type === "mobile" ? { width: 393, height: 852 } : { width: 1280, height: 800 };
+        try {
+          const png = await renderHtmlToPng({ html: design.html, ...size, scale: THUMBNAIL_SCALE });
+          await invoke("save_thumbnail", { sessionCwd, filePath: path, png: Array.from(png) });
+          saved++;
+        } catch (err) {
+          console.error("[thumbnails] Failed to render", path, err);
+        }
+      }
+      if (!cancelled && saved > 0) {
+        queryClient.invalidateQueries({ queryKey: designKeys.session(sessionCwd) });
+      }
+    })().catch(console.error);
+
+    return () => {
+      cancelled = true;
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps -- versionKey stands in for designs
+  }, [sessionCwd, versionKey, queryClient]);
+}
+
+async function loadThumbnail(sessionCwd: string, filePath: string): Promise<string | null> {
+  const bytes = await invoke<number[] | null>("get_thumbnail", { sessionCwd, filePath });
+  if (!bytes) return null;
+  let binary = "";
+  for (let i = 0; i < bytes.length; i += 0x8000) {
+    binary += String.fromCharCode(...bytes.slice(i, i + 0x8000));
+  }
+  return `data:image/png;base64,${btoa(binary)}`;
+}
+
+/**
+ * Data URL of a design's cached thumbnail, or null while there is none.
+ * Keyed by `thumbnailPath`, which changes with every new version of the file.
+ */
+export function useThumbnailSrc(
+  sessionCwd: string | undefined,
+  filePath: string | undefined,
+  thumbnailPath: string | null | undefined
+) {
+  const { data } = useQuery({
+    queryKey: ["thumbnails", thumbnailPath],
+    queryFn: () => loadThumbnail(sessionCwd!, filePath!),
+    enabled: !!sessionCwd && !!filePath && !!thumbnailPath,
+    staleTime: Infinity,
+  });
+  return thumbnailPath ? (data ?? null) : null;
+}
@@ -0,0 +1,40 @@
+/**
+ * Error payload rejected by Rust commands that return `AppResult`.
+ * Commands that still return `Result<_, String>` reject with a plain string.
+ */
+export type AppErrorCode =
+  | "opencode_not_found"
+  | "io"
+  | "json"
+  | "server_start"
+  | "server_not_running"
+  | "session_not_found"
+  | "project_not_ready"
+  | "port_in_use"
+  | "skill_install_failed"
+  | "busy"
+  | "timeout"
+  | "custom";
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as AppError).code === "string" &&
+    typeof (err as AppError).message === "string"
+  );
+}
+
+/**
+ * Human-readable message for anything thrown by `invoke` or JS code
+ */
+export function getErrorMessage(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  if (isAppError(err)) return err.message;
+  return String(err);
+}
@@ -2,6 +2,8 @@
  * Async utilities for proper cleanup and error handling
  */
 
+import { getErrorMessage } from "@/lib/app-error";
+
 /**
  * Creates a managed timeout that can be cancelled.
  * Returns a cleanup function that cancels the timeout.
@@ -45,7 +47,7 @@ export async function withErrorHandler<T>(
   try {
     return await operation();
   } catch (error) {
-    const message = error instanceof Error ? error.message : String(error);
+    const message = getErrorMessage(error);
     console.error(`[${context}] Error:`, message);
 
     if (fallback !== undefined) {
@@ -3,7 +3,9 @@ import JSZip from "jszip";
 import html2canvas from "html2canvas-pro";
 import { save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 import type { DesignFile } from "@/hooks/use-designs";
+import { getErrorMessage } from "@/lib/app-error";
 
 export function copyToClipboard(html: string | undefined) {
   if (!html) {
@@ -40,6 +42,36 @@ export function downloadHtml(design: { html: string; title: string } | null) {
   toast.success(`Downloaded ${filename}`);
 }
 
+/**
+ * Save a design file to a location picked in the native save dialog
+ */
+export async function saveHtmlAs(filePath: string, title: string) {
+  const destPath = await save({
+    defaultPath: title.toLowerCase().replace(/\s+/g, "-") + ".html",
+    filters: [{ name: "HTML", extensions: ["html"] }],
+  });
+  if (!destPath) return;
+
+  try {
+    const written = await invoke<string>("export_design_html", { filePath, destPath });
+    toast.success(`Saved to ${written}`);
+  } catch (err) {
+    toast.error(`Failed to save: ${getErrorMessage(err)}`);
+  }
+}
+
+/**
+ * Fork a design in place as `<name>-copy.html`; the session watcher picks it up
+ */
+export async function duplicateDesign(filePath: string) {
+  try {
+    await invoke<string>("duplicate_design", { filePath });
+    toast.success("Screen duplicated");
+  } catch (err) {
+    toast.error(`Failed to duplicate: ${getErrorMessage(err)}`);
+  }
+}
+
 export function downloadAllDesigns(designs: DesignFile[]) {
   if (designs.length === 0) {
     toast.error("No designs to download");
@@ -145,6 +177,40 @@ export async function renderHtmlToPng({
   }
 }
 
+/**
+ * Render a session's design at the size its screen type calls for
+ * (393×852 for mobile, 1280×800 for web unless overridden)
+ */
+export async function captureDesign(
+  sessionCwd: string,
+  filename: string,
+  { width, height, scale = 2 }: Omit<RenderOptions, "html"> = {}
+): Promise<Uint8Array> {
+  const capture = await invoke<{ html: string; width: number; height: number }>(
+    "resolve_design_capture",
+    { sessionCwd, filename, width, height }
+  );
+  return renderHtmlToPng({ ...capture, scale });
+}
+
+export type DeviceFrame = "iphone-14-pro" | "pixel-7";
+
+/**
+ * Render a mobile design and composite it into a bundled device bezel
+ */
+export async function renderFramedPng(
+  design: Pick<DesignFile, "html" | "screen_type">,
+  frame: DeviceFrame,
+  scale = 2
+): Promise<Uint8Array> {
+  if (design.screen_type !== "mobile") {
+    throw new Error("Device frames only apply to mobile screens");
+  }
+  const png = await renderHtmlToPng({ html: design.html, width: 393, height: 852, scale });
+  const framed = await invoke<number[]>("frame_screenshot", { png: Array.from(png), frame });
+  return Uint8Array.from(framed);
+}
+
 /**
  * Generate PNG to a specific path (no dialog)
  */
@@ -185,10 +251,97 @@ export async function exportAsPng({
   }
 }
 
+type ColorTheme = "light" | "dark";
+
+const THEME_HINTS = /prefers-color-scheme|\bdark:|data-theme/;
+
+/**
+ * Force a design into one color theme: tag `<html>` for class/attribute based
+ * themes and pin `prefers-color-scheme` media queries to the chosen side
+ */
+export function applyColorTheme(html: string, theme: ColorTheme): string {
+  const other = theme === "dark" ? "light" : "dark";
+  const pinned = html
+    .replace(new RegExp(`\\(\\s*prefers-color-scheme\\s*:\\s*${theme}\\s*\\)`, "g"), "(min-width: 0px)")
+    .replace(new RegExp(`\\(\\s*prefers-color-scheme\\s*:\\s*${other}\\s*\\)`, "g"), "(max-width: 0px)");
+  const style = `<style>:root{color-scheme:${theme}}</style>`;
+  return pinned
+    .replace(/<html\b([^>]*)>/i, (_, attrs: string) => {
+      const cleaned = attrs.replace(/\sdata-theme="[^"]*"/i, "");
+      const withClass = /\sclass="/i.test(cleaned)
+        ? cleaned.replace(/\sclass="([^"]*)"/i, (_m, cls: string) => {
+            const classes = cls.split(/\s+/).filter((c) => c && c !== "dark" && c !== "light");
+            return ` class="${[...classes, theme].join(" ")}"`;
+          })
+        : `${cleaned} class="${theme}"`;
+      return `<html${withClass} data-theme="${theme}">`;
+    })
+    .replace("</head>", `${style}</head>`);
+}
+
+/**
+ * Export `<name>-light.png` and `<name>-dark.png` next to a chosen path.
+ * Designs without any theme switching render once and save that image as both.
+ */
+export async function exportThemeVariants({
+  html,
+  title,
+  width = 393,
+  height = 852,
+  scale = 2,
+}: RenderOptions & { title: string }): Promise<void> {
+  const base = title.toLowerCase().replace(/\s+/g, "-");
+  const filePath = await save({
+    defaultPath: `${base}.png`,
+    filters: [{ name: "PNG Image", extensions: ["png"] }],
+  });
+  if (!filePath) return;
+
+  const stem = filePath.replace(/\.png$/i, "");
+  const toastId = toast.loading("Generating light and dark PNGs...");
+  try {
+    const themed = THEME_HINTS.test(html);
+    const light = await renderHtmlToPng({
+      html: themed ? applyColorTheme(html, "light") : html,
+      width,
+      height,
+      scale,
+    });
+    const dark = themed
+      ? await renderHtmlToPng({ html: applyColorTheme(html, "dark"), width, height, scale })
+      : light;
+
+    await writeFile(`${stem}-light.png`, light);
+    await writeFile(`${stem}-dark.png`, dark);
+    toast.success(
+      themed
+        ? `Saved ${base}-light.png and ${base}-dark.png`
+        : "No theme switching found; saved the same image for light and dark",
+      { id: toastId }
+    );
+  } catch (error) {
+    console.error("Theme export failed:", error);
+    toast.error(`Export failed: ${error instanceof Error ? error.message : "Unknown error"}`, { id: toastId });
+  }
+}
+
 interface ExportImagesOptions {
   designs: DesignFile[];
   sessionName?: string;
   platform?: "mobile" | "web";
+  /** Refuse exports with more screens than this */
+  maxScreens?: number;
+}
+
+/** Default cap on screens per image export, same as `MAX_EXPORT_SCREENS` in designs.rs */
+export const MAX_IMAGE_EXPORT_SCREENS = 100;
+
+/** Rough cost of one render: the settle delay in renderHtmlToPng plus html2canvas */
+const ESTIMATED_MS_PER_SCREEN = 1500;
+
+function formatEstimate(count: number): string {
+  const seconds = Math.ceil((count * ESTIMATED_MS_PER_SCREEN) / 1000);
+  return seconds < 60 ? `~${seconds}s` : `~${Math.ceil(seconds / 60)} min`;
 }
 
 /**
@@ -198,11 +351,18 @@ export async function exportImages({
   designs,
   sessionName = "designs",
   platform = "mobile",
+  maxScreens = MAX_IMAGE_EXPORT_SCREENS,
 }: ExportImagesOptions): Promise<void> {
   if (designs.length === 0) {
     toast.error("No designs to export");
     return;
   }
+  if (designs.length > maxScreens) {
+    toast.error(
+      `Too many screens (${designs.length}): image export is limited to ${maxScreens}. Select fewer screens and export in batches.`
+    );
+    return;
+  }
 
   // Ask user for save location
   const zipName = `${sessionName}.zip`;
@@ -213,12 +373,7 @@ export async function exportImages({
 
   if (!filePath) return;
 
-  const dimensions =
-    platform === "mobile"
-      ? { width: 393, height: 852 }
-      : { width: 1280, height: 800 };
-
-  const toastId = toast.loading(`Generating ${designs.length} images...`);
+  const toastId = toast.loading(`Generating ${designs.length} images (${formatEstimate(designs.length)})...`);
   const zip = new JSZip();
   
   // Create a folder inside the zip with the session name
@@ -228,27 +383,59 @@ export async function exportImages({
     return;
   }
 
+  const usedNames = new Set<string>();
+  // The screen being rendered finishes; nothing after it starts
+  let cancelled = false;
+  const cancel = {
+    label: "Cancel",
+    onClick: () => {
+      cancelled = true;
+    },
+  };
+  let rendered = 0;
   try {
-    for (let i = 0; i < designs.length; i++) {
+    for (let i = 0; i < designs.length && !cancelled; i++) {
       const design = designs[i];
-      toast.loading(`Rendering ${i + 1}/${designs.length}: ${design.title}`, { id: toastId });
-      
+      toast.loading(`Rendering ${i + 1}/${designs.length}: ${design.title}`, { id: toastId, action: cancel });
+
+      // Size each screen by its own type so mixed mobile/web sessions export correctly
+      const screenType = design.screen_type || platform;
+      const dimensions =
+        screenType === "mobile"
+          ? { width: 393, height: 852 }
+          : { width: 1280, height: 800 };
+
       const bytes = await renderHtmlToPng({
         html: design.html,
         ...dimensions,
         scale: 2,
       });
-      
-      const filename = design.title.toLowerCase().replace(/\s+/g, "-") + ".png";
+
+      // Screens can share a title; suffix repeats instead of overwriting
+      const base = design.title.toLowerCase().replace(/\s+/g, "-");
+      let filename = `${base}.png`;
+      for (let n = 2; usedNames.has(filename); n++) filename = `${base}-${n}.png`;
+      usedNames.add(filename);
       folder.file(filename, bytes);
+      rendered++;
+    }
+
+    if (rendered === 0) {
+      toast.info("Export cancelled", { id: toastId });
+      return;
     }
 
-    toast.loading("Saving...", { id: toastId });
+    toast.loading("Saving...", { id: toastId, action: undefined });
     const blob = await zip.generateAsync({ type: "blob" });
     const buffer = await blob.arrayBuffer();
     await writeFile(filePath, new Uint8Array(buffer));
 
-    toast.success(`Exported ${designs.length} images`, { id: toastId });
+    toast.success(
+      cancelled
+        ? `Export cancelled: saved ${rendered} of ${designs.length} images`
+        : `Exported ${designs.length} images`,
+      { id: toastId }
+    );
   } catch (error) {
     console.error("Image export failed:", error);
     toast.error(`Export failed: ${error instanceof Error ? error.message : "Unknown error"}`, { id: toastId });
@@ -4,6 +4,7 @@ import { SidebarProvider, SidebarInset } from "@dilag/ui/sidebar"
 import { AppSidebar } from "@/components/blocks/layout/app-sidebar"
 import { AppProviders } from "@/components/app-providers"
 import { useZoom } from "@/hooks/use-zoom"
+import { useInstanceGuard } from "@/hooks/use-instance-guard"
 
 export const Route = createRootRoute({
   component: RootLayout,
@@ -12,6 +13,7 @@ export const Route = createRootRoute({
 function RootLayout() {
   // Initialize zoom persistence (restores saved zoom level on mount)
   useZoom()
+  useInstanceGuard()
 
   // Note: Suspense is NOT used here because it causes iframe remounting issues
   // with lazy-loaded routes. The iframes load external scripts (Tailwind CDN)
@@ -18,10 +18,12 @@ import { ModelSelectorButton } from "@/components/blocks/selectors/model-selecto
 import { AgentSelectorButton } from "@/components/blocks/selectors/agent-selector-button";
 import { ThinkingModeSelector } from "@/components/blocks/selectors/thinking-mode-selector";
 import { useSessions } from "@/hooks/use-sessions";
+import { getLastActiveSession } from "@/hooks/use-session-data";
 import { type Platform } from "@/context/session-store";
 import { cn } from "@/lib/utils";
 import { ArrowUp, Monitor, Smartphone } from "@solar-icons/react";
 import { createFileRoute, useNavigate } from "@tanstack/react-router";
+import { useEffect } from "react";
 import { parseAsStringLiteral, useQueryState } from "nuqs";
 
 const SUGGESTIONS = [
@@ -35,6 +37,9 @@ export const Route = createFileRoute("/")({
   component: LandingPage,
 });
 
+// Only restore the last session on launch, not every time the user comes home
+let lastSessionRestoreAttempted = false;
+
 function LandingPage() {
   const navigate = useNavigate();
   const { createSession, isServerReady } = useSessions();
@@ -43,6 +48,16 @@ function LandingPage() {
     parseAsStringLiteral(["web", "mobile"] as const).withDefault("web")
   );
 
+  useEffect(() => {
+    if (lastSessionRestoreAttempted) return;
+    lastSessionRestoreAttempted = true;
+    getLastActiveSession()
+      .then((sessionId) => {
+        if (sessionId) navigate({ to: "/studio/$sessionId", params: { sessionId } });
+      })
+      .catch(console.error);
+  }, [navigate]);
+
   const handleSubmit = async (
     text: string,
     files?: import("ai").FileUIPart[],
@@ -2,6 +2,7 @@ import { createFileRoute } from "@tanstack/react-router";
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { openUrl } from "@tauri-apps/plugin-opener";
+import { toast } from "sonner";
 import {
   MagicStick,
   TrashBinMinimalistic,
@@ -22,6 +23,7 @@ import {
   DialogHeader,
   DialogTitle,
 } from "@dilag/ui/dialog";
+import { getErrorMessage } from "@/lib/app-error";
 
 export const Route = createFileRoute("/skills")({
   component: SkillsPage,
@@ -31,6 +33,8 @@ interface SkillInfo {
   name: string;
   path: string;
   is_symlink: boolean;
+  /** Symlink whose target is gone */
+  broken: boolean;
 }
 
 interface SkillPreview {
@@ -48,6 +52,7 @@ interface SkillInstallResult {
   success: boolean;
   installed: string[];
   error: string | null;
+  logs: string[];
 }
 
 function SkillsPage() {
@@ -83,6 +88,18 @@ function SkillsPage() {
     loadSkills();
   }, [loadSkills]);
 
+  const handleRepair = useCallback(async () => {
+    try {
+      const report = await invoke<{ removed: string[]; added: string[] }>("repair_skills");
+      toast.success(
+        `Removed ${report.removed.length} broken link${report.removed.length === 1 ? "" : "s"}, linked ${report.added.length} skill${report.added.length === 1 ? "" : "s"}`
+      );
+      await loadSkills();
+    } catch (error) {
+      toast.error(`Failed to repair skills: ${getErrorMessage(error)}`);
+    }
+  }, [loadSkills]);
+
   const handlePreview = async (e: React.FormEvent) => {
     e.preventDefault();
     const source = sourceInput.trim();
@@ -110,7 +127,7 @@ function SkillsPage() {
         setPreviewError(result.error || "Failed to fetch skills");
       }
     } catch (error) {
-      setPreviewError(String(error));
+      setPreviewError(getErrorMessage(error));
     } finally {
       setPreviewing(false);
     }
@@ -167,7 +184,7 @@ function SkillsPage() {
         });
       }
     } catch (error) {
-      setInstallStatus({ type: "error", message: String(error) });
+      setInstallStatus({ type: "error", message: getErrorMessage(error) });
     } finally {
       setInstalling(false);
     }
@@ -425,6 +442,15 @@ function SkillsPage() {
                                     built-in
                                   </span>
                                 )}
+                                {skill.broken && (
+                                  <button
+                                    onClick={handleRepair}
+                                    title="The linked skill was moved or deleted. Click to repair."
+                                    className="text-[10px] px-1.5 py-0.5 rounded bg-destructive/10 text-destructive hover:bg-destructive/20"
+                                  >
+                                    broken · repair
+                                  </button>
+                                )}
                               </div>
                             </div>
                             {!isBuiltin && (
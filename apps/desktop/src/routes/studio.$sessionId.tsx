@@ -4,8 +4,9 @@ import type { ImperativePanelHandle } from "react-resizable-panels";
 import { invoke } from "@tauri-apps/api/core";
 import { useQueryClient } from "@tanstack/react-query";
 import { useSessions } from "@/hooks/use-sessions";
-import { useSessionMutations } from "@/hooks/use-session-data";
-import { useSessionDesigns, designKeys } from "@/hooks/use-designs";
+import { setLastActiveSession, useSessionMutations } from "@/hooks/use-session-data";
+import { useSessionDesigns, useSessionDesignWatch, designKeys, getDesignPath } from "@/hooks/use-designs";
+import { useThumbnailCache } from "@/hooks/use-thumbnails";
 import { usePngGenerator } from "@/hooks/use-png-generator";
 import { useSDK } from "@/context/global-events";
 import { useChatWidth } from "@/hooks/use-chat-width";
@@ -31,6 +32,7 @@ import { PreviewCarousel } from "@/components/blocks/preview/preview-carousel";
 import { AttachmentBridgeProvider } from "@/context/attachment-bridge";
 import { ScreenCaptureProvider, useScreenCaptureContext } from "@/context/screen-capture-context";
 import { toast } from "sonner";
+import { getErrorMessage } from "@/lib/app-error";
 
 export const Route = createFileRoute("/studio/$sessionId")({
   component: StudioPage,
@@ -55,6 +57,7 @@ function StudioPage() {
   const [newName, setNewName] = useState("");
   const [deleteTarget, setDeleteTarget] = useState<{
     filename: string;
+    folder?: string | null;
     title: string;
   } | null>(null);
   const [previewOpen, setPreviewOpen] = useState(false);
@@ -70,6 +73,8 @@ function StudioPage() {
 
   const currentSession = sessions.find((s: { id: string }) => s.id === sessionId);
   const { data: designs = [] } = useSessionDesigns(currentSession?.cwd);
+  useSessionDesignWatch(currentSession?.cwd);
+  useThumbnailCache(currentSession?.cwd, designs);
   const isWritingScreen = useIsWritingScreen(currentSession?.id ?? null);
 
   // Show canvas loading when the AI is actively running or a write/edit tool is pending
@@ -87,6 +92,11 @@ function StudioPage() {
     selectSession(sessionId);
   }, [sessionId, selectSession]);
 
+  // Remember this session so the next launch reopens it
+  useEffect(() => {
+    setLastActiveSession(sessionId).catch(console.error);
+  }, [sessionId]);
+
   // Sync screen positions when designs change
   useEffect(() => {
     if (designs.length === 0) return;
@@ -128,7 +138,7 @@ function StudioPage() {
   const handleDeleteScreen = useCallback(async () => {
     if (!deleteTarget || !currentSession?.cwd) return;
 
-    const filePath = `${currentSession.cwd}/screens/${deleteTarget.filename}`;
+    const filePath = getDesignPath(currentSession.cwd, deleteTarget);
     try {
       await invoke("delete_design", { filePath });
       // Remove from positions
@@ -142,7 +152,7 @@ function StudioPage() {
       });
       toast.success(`Deleted ${deleteTarget.title}`);
     } catch (err) {
-      toast.error(`Failed to delete: ${err}`);
+      toast.error(`Failed to delete: ${getErrorMessage(err)}`);
     }
     setDeleteTarget(null);
   }, [deleteTarget, currentSession?.cwd, sessionId, screenPositions, setScreenPositions, queryClient]);
@@ -158,7 +168,7 @@ function StudioPage() {
     (filename: string) => {
       const design = designs.find((d) => d.filename === filename);
       if (design) {
-        setDeleteTarget({ filename, title: design.title });
+        setDeleteTarget({ filename, folder: design.folder, title: design.title });
       }
     },
     [designs]
@@ -229,7 +239,7 @@ function StudioPage() {
         const firstSelectedId = Array.from(selectedScreenIds)[0];
         const design = designs.find((d) => d.filename === firstSelectedId);
         if (design) {
-          setDeleteTarget({ filename: design.filename, title: design.title });
+          setDeleteTarget({ filename: design.filename, folder: design.folder, title: design.title });
         }
       }
     };